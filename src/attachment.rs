@@ -0,0 +1,144 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Resolve where a downloaded attachment is written.
+/// Uses the explicit output path when given (joining the filename when it is an existing
+/// directory), otherwise current dir + filename. An existing file is only replaced with
+/// `overwrite`.
+pub fn resolve_output_path(
+    output_path: Option<&Path>,
+    filename: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let path = match output_path {
+        Some(p) if p.is_dir() => p.join(sanitize_filename(filename)?),
+        Some(p) => p.to_path_buf(),
+        None => std::env::current_dir()?.join(sanitize_filename(filename)?),
+    };
+    if !overwrite && path.exists() {
+        anyhow::bail!(
+            "{} already exists (use --overwrite to replace it)",
+            path.display()
+        );
+    }
+    Ok(path)
+}
+
+/// Make an attachment's metadata filename safe to join onto the output directory.
+/// Only the last path component is kept (so `../` and absolute paths can't escape the
+/// directory), characters that are illegal on common filesystems become `_`, and names
+/// with nothing usable left (`..`, `/`) are rejected.
+pub fn sanitize_filename(filename: &str) -> Result<String> {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+    // Windows drops trailing dots and spaces, which would turn `...` into a traversal
+    let name = name.trim_end_matches(['.', ' ']).trim_start();
+
+    if name.is_empty() {
+        anyhow::bail!(
+            "Refusing to write attachment with unsafe filename {:?}",
+            filename
+        );
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "atlassian-cli-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_explicit_output_path() {
+        let path = resolve_output_path(
+            Some(Path::new("/tmp/no-such-dir/out.pdf")),
+            "report.pdf",
+            false,
+        )
+        .unwrap();
+        assert_eq!(path, PathBuf::from("/tmp/no-such-dir/out.pdf"));
+    }
+
+    #[test]
+    fn test_default_output_path() {
+        let path = resolve_output_path(None, "no-such-report.pdf", false).unwrap();
+        assert_eq!(
+            path,
+            std::env::current_dir().unwrap().join("no-such-report.pdf")
+        );
+    }
+
+    #[test]
+    fn test_directory_output_path_joins_filename() {
+        let dir = temp_dir("attachment-dir");
+        let path = resolve_output_path(Some(&dir), "report.pdf", false).unwrap();
+        assert_eq!(path, dir.join("report.pdf"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_traversal_filename_is_confined_to_output_dir() {
+        let dir = temp_dir("attachment-traversal");
+        let path = resolve_output_path(Some(&dir), "../../etc/passwd", false).unwrap();
+        assert_eq!(path, dir.join("passwd"));
+        assert!(path.starts_with(&dir));
+
+        let path = resolve_output_path(Some(&dir), "/etc/shadow", false).unwrap();
+        assert_eq!(path, dir.join("shadow"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("report.pdf").unwrap(), "report.pdf");
+        assert_eq!(
+            sanitize_filename("..\\..\\Windows\\win.ini").unwrap(),
+            "win.ini"
+        );
+        assert_eq!(
+            sanitize_filename("Q3: plan <draft>?.docx").unwrap(),
+            "Q3_ plan _draft__.docx"
+        );
+        assert_eq!(
+            sanitize_filename("회의록 2024.pdf").unwrap(),
+            "회의록 2024.pdf"
+        );
+        assert!(sanitize_filename("..").is_err());
+        assert!(sanitize_filename("../").is_err());
+        assert!(sanitize_filename("").is_err());
+    }
+
+    #[test]
+    fn test_existing_file_requires_overwrite() {
+        let dir = temp_dir("attachment-collision");
+        let existing = dir.join("report.pdf");
+        std::fs::write(&existing, b"old").unwrap();
+
+        let err = resolve_output_path(Some(&dir), "report.pdf", false).unwrap_err();
+        assert!(err.to_string().contains("--overwrite"));
+        let err = resolve_output_path(Some(&existing), "other.pdf", false).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        let path = resolve_output_path(Some(&dir), "report.pdf", true).unwrap();
+        assert_eq!(path, existing);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,17 +1,31 @@
+use crate::attachment;
 use crate::config::Config;
 use crate::confluence::fields::{apply_v2_filtering, build_search_expand};
 use crate::filter;
 use crate::http;
-use crate::markdown::confluence_to_markdown;
+use crate::markdown::{confluence_to_markdown, confluence_to_text};
+use crate::pagination;
+use crate::util;
 use anyhow::Result;
+use regex::Regex;
 use reqwest::Client;
 use serde_json::{Value, json};
+use std::collections::HashSet;
 use std::io::{self, Write};
-use std::time::Duration;
-use tokio::time::sleep;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// `type = page`, `type != comment`, `type in (...)`, `type not in (...)`
+static TYPE_CLAUSE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\btype\s*(=|!=|\bin\b|\bnot\s+in\b)").unwrap());
+/// A double- or single-quoted CQL string, honouring backslash escapes
+static QUOTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'"#).unwrap());
 
 const MAX_LIMIT: u32 = 250;
 const SEARCH_BODY_LIMIT: u32 = 50;
+/// Body and version of a page on the Data Center content API
+const DC_PAGE_EXPAND: &str = "body.storage,version";
 
 fn apply_space_filter(cql: &str, config: &Config) -> String {
     if config.confluence.spaces_filter.is_empty() {
@@ -32,10 +46,53 @@ fn apply_space_filter(cql: &str, config: &Config) -> String {
             .map(|s| format!("\"{}\"", s))
             .collect::<Vec<_>>()
             .join(",");
-        format!("space IN ({}) AND ({})", spaces, cql)
+        if cql.trim().is_empty() {
+            format!("space IN ({})", spaces)
+        } else {
+            format!("space IN ({}) AND ({})", spaces, cql)
+        }
+    }
+}
+
+/// Keep only the given top-level keys of each search result (`--fields`), dropping the rest
+pub fn project_fields(result: &mut Value, fields: &[String]) {
+    let Some(items) = result.get_mut("items").and_then(|i| i.as_array_mut()) else {
+        return;
+    };
+    for item in items {
+        if let Some(obj) = item.as_object_mut() {
+            obj.retain(|key, _| fields.iter().any(|f| f == key));
+        }
+    }
+}
+
+/// Restrict a query to one content type (`page`, `blogpost`, ...) unless it already has a type clause
+pub fn apply_type_filter(cql: &str, content_type: Option<&str>) -> String {
+    let Some(content_type) = content_type else {
+        return cql.to_string();
+    };
+
+    // Quoted strings can't hold a clause: `text ~ "type = page"` still needs the filter
+    let unquoted = QUOTED_RE.replace_all(cql, "\"\"");
+    if TYPE_CLAUSE_RE.is_match(&unquoted) {
+        cql.to_string()
+    } else if cql.trim().is_empty() {
+        format!("type = {}", content_type)
+    } else {
+        format!("type = {} AND ({})", content_type, cql)
     }
 }
 
+/// Catch common CQL mistakes locally instead of surfacing an opaque 400.
+/// Run on the final query, after filter injection, so a configured `spaces_filter`
+/// turns an empty query into a valid one.
+fn validate_cql(cql: &str) -> Result<()> {
+    if cql.trim().is_empty() {
+        anyhow::bail!("CQL query is empty");
+    }
+    util::check_query_syntax(cql, "CQL")
+}
+
 fn build_next_url(links_base: &str, next_path: &str) -> String {
     if next_path.starts_with("http") {
         next_path.to_string()
@@ -45,37 +102,48 @@ fn build_next_url(links_base: &str, next_path: &str) -> String {
     }
 }
 
+/// One page of results. Pass the returned `cursor` back (with the same query) to continue.
 pub async fn search(
     query: &str,
     limit: u32,
     include_all_fields: Option<bool>,
     additional_expand: Option<Vec<String>>,
+    cursor: Option<&str>,
     as_markdown: bool,
     config: &Config,
 ) -> Result<Value> {
     let final_cql = apply_space_filter(query, config);
+    validate_cql(&final_cql)?;
     let client = http::client(config);
-    let url = format!("{}/wiki/rest/api/search", config.base_url());
+    let url = format!("{}/rest/api/search", config.wiki_url());
     let expand = build_search_expand(include_all_fields, additional_expand);
 
-    let effective_limit = limit.min(MAX_LIMIT).min(SEARCH_BODY_LIMIT);
-
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .query(&[
-            ("cql", final_cql.as_str()),
-            ("limit", &effective_limit.to_string()),
-            ("expand", &expand),
-        ])
-        .send()
-        .await?;
+    // Bodies are expanded, which the search API caps well below MAX_LIMIT
+    let effective_limit =
+        pagination::clamp_limit(limit, MAX_LIMIT.min(SEARCH_BODY_LIMIT), config.quiet);
+
+    let mut query_params = vec![
+        ("cql", final_cql),
+        ("limit", effective_limit.to_string()),
+        ("expand", expand),
+    ];
+    if let Some(cursor) = cursor {
+        query_params.push(("cursor", cursor.to_string()));
+    }
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&query_params),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Search failed ({}): {}", status, body);
+        anyhow::bail!("Search failed ({}): {}", status, http::error_message(&body));
     }
 
     let mut data: Value = response.json().await?;
@@ -88,24 +156,81 @@ pub async fn search(
         "count": items.len(),
         "total": total
     });
+    let cursor = data["_links"]["next"].as_str().and_then(next_cursor);
+    if !config.quiet
+        && let Some(warning) = pagination::truncation_warning(
+            effective_limit,
+            items.len(),
+            Some(total),
+            cursor.is_some(),
+        )
+    {
+        eprintln!("{}", warning);
+    }
+    if let Some(cursor) = cursor {
+        output["cursor"] = json!(cursor);
+    }
 
     filter::apply(&mut output, config);
     Ok(output)
 }
 
+/// The `cursor` query parameter of a `_links.next` path
+fn next_cursor(next_path: &str) -> Option<String> {
+    let url = reqwest::Url::parse("http://localhost")
+        .ok()?
+        .join(next_path)
+        .ok()?;
+    url.query_pairs()
+        .find(|(key, _)| key == "cursor")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Count matching content via `totalSize` without fetching bodies
+pub async fn count(query: &str, config: &Config) -> Result<Value> {
+    let final_cql = apply_space_filter(query, config);
+    validate_cql(&final_cql)?;
+    let client = http::client(config);
+    let url = format!("{}/rest/api/search", config.wiki_url());
+
+    // limit=0 is rejected, so request a single result and read totalSize
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&[("cql", final_cql.as_str()), ("limit", "1")]),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Search failed ({}): {}", status, http::error_message(&body));
+    }
+
+    let data: Value = response.json().await?;
+    Ok(json!({ "total": data["totalSize"].as_u64().unwrap_or(0) }))
+}
+
 pub async fn search_all(
     query: &str,
     include_all_fields: Option<bool>,
     additional_expand: Option<Vec<String>>,
     stream: bool,
+    paging: pagination::Paging,
     as_markdown: bool,
     config: &Config,
 ) -> Result<Value> {
     let final_cql = apply_space_filter(query, config);
+    validate_cql(&final_cql)?;
     let client = http::client(config);
-    let base_url = config.base_url();
-    let initial_url = format!("{}/wiki/rest/api/search", base_url);
+    let base_url = config.wiki_url();
+    let initial_url = format!("{}/rest/api/search", base_url);
     let expand = build_search_expand(include_all_fields, additional_expand);
+    // Bodies are expanded, which the search API caps well below MAX_LIMIT
+    let page_size = paging.page_size(SEARCH_BODY_LIMIT, config.quiet);
+    let max = paging.max;
 
     let mut all_items: Vec<Value> = Vec::new();
     let mut page_num = 1;
@@ -116,15 +241,24 @@ pub async fn search_all(
         let mut data = if let Some(ref url) = next_url {
             fetch_page(&client, url, config).await?
         } else {
-            fetch_initial_page(&client, &initial_url, &final_cql, &expand, config).await?
+            fetch_initial_page(
+                &client,
+                &initial_url,
+                &final_cql,
+                &expand,
+                page_size,
+                config,
+            )
+            .await?
         };
 
         if page_num == 1 {
             total_size = data["totalSize"].as_u64().unwrap_or(0);
         }
 
-        let items = extract_content_from_results(&mut data, as_markdown);
+        let mut items = extract_content_from_results(&mut data, as_markdown);
         let count = items.len();
+        let capped = pagination::apply_cap(&mut items, all_items.len(), max);
 
         if stream {
             for item in &items {
@@ -135,29 +269,29 @@ pub async fn search_all(
 
         all_items.extend(items);
 
-        eprintln!(
-            "  Page {}: {} items (fetched: {}/{})",
+        pagination::report_page(
+            config,
             page_num,
+            "items",
             count,
             all_items.len(),
-            total_size
+            total_size,
+            max,
         );
 
         let next_path = data["_links"]["next"].as_str();
-        if next_path.is_none() || count == 0 {
+        if next_path.is_none() || count == 0 || capped {
             break;
         }
 
-        let links_base = data["_links"]["base"].as_str().unwrap_or(base_url);
+        let links_base = data["_links"]["base"].as_str().unwrap_or(&base_url);
         next_url = Some(build_next_url(links_base, next_path.unwrap()));
         page_num += 1;
-        sleep(Duration::from_millis(
-            config.performance.rate_limit_delay_ms,
-        ))
-        .await;
     }
 
-    eprintln!("\nTotal: {} items fetched", all_items.len());
+    if !config.quiet && !config.progress_json {
+        eprintln!("\nTotal: {} items fetched", all_items.len());
+    }
 
     if stream {
         Ok(json!({"streamed": true, "total": all_items.len()}))
@@ -174,38 +308,41 @@ async fn fetch_initial_page(
     url: &str,
     cql: &str,
     expand: &str,
+    limit: u32,
     config: &Config,
 ) -> Result<Value> {
-    let limit = SEARCH_BODY_LIMIT.to_string();
-    let response = client
-        .get(url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .query(&[("cql", cql), ("limit", &limit), ("expand", expand)])
-        .send()
-        .await?;
+    let limit = limit.to_string();
+    let response = http::send_timed(
+        client
+            .get(url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&[("cql", cql), ("limit", &limit), ("expand", expand)]),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Search failed ({}): {}", status, body);
+        anyhow::bail!("Search failed ({}): {}", status, http::error_message(&body));
     }
 
     response.json().await.map_err(Into::into)
 }
 
 async fn fetch_page(client: &Client, url: &str, config: &Config) -> Result<Value> {
-    let response = client
-        .get(url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .get(url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Search failed ({}): {}", status, body);
+        anyhow::bail!("Search failed ({}): {}", status, http::error_message(&body));
     }
 
     response.json().await.map_err(Into::into)
@@ -219,17 +356,22 @@ pub async fn get_page(
     config: &Config,
 ) -> Result<Value> {
     let client = http::client(config);
-    let url = format!("{}/wiki/api/v2/pages/{}", config.base_url(), page_id);
+    let url = page_url(page_id, config);
 
-    let query_params = apply_v2_filtering(include_all_fields, additional_includes);
+    let query_params = if config.is_datacenter() {
+        vec![("expand".to_string(), DC_PAGE_EXPAND.to_string())]
+    } else {
+        apply_v2_filtering(include_all_fields, additional_includes)
+    };
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .query(&query_params)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&query_params),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -247,85 +389,590 @@ pub async fn get_page(
     Ok(data)
 }
 
-pub async fn get_page_children(page_id: &str, config: &Config) -> Result<Value> {
+/// A page on the v2 API, or on the content API on Data Center, which has no v2
+fn page_url(page_id: &str, config: &Config) -> String {
+    if config.is_datacenter() {
+        format!("{}/rest/api/content/{}", config.wiki_url(), page_id)
+    } else {
+        format!("{}/api/v2/pages/{}", config.wiki_url(), page_id)
+    }
+}
+
+fn ensure_cloud(command: &str, config: &Config) -> Result<()> {
+    if config.is_datacenter() {
+        anyhow::bail!(
+            "confluence {} is not supported on Data Center (it needs the Cloud v2 API)",
+            command
+        );
+    }
+    Ok(())
+}
+
+/// ID of the page with exactly this title in a space. With no exact match, the error
+/// lists pages whose titles contain the text; with several, it lists all of them.
+pub async fn find_page_by_title(space: &str, title: &str, config: &Config) -> Result<String> {
     let client = http::client(config);
-    let url = format!(
-        "{}/wiki/api/v2/pages/{}/children",
-        config.base_url(),
-        page_id
+    let url = format!("{}/rest/api/search", config.wiki_url());
+
+    let exact = format!(
+        "space = {} AND title = {} AND type = page",
+        cql_string(space),
+        cql_string(title)
     );
+    let mut data = fetch_initial_page(&client, &url, &exact, "", SEARCH_BODY_LIMIT, config).await?;
+    let matches = extract_content_from_results(&mut data, false);
+
+    match matches.as_slice() {
+        [page] => page_id_of(page).ok_or_else(|| anyhow::anyhow!("Search result has no page id")),
+        [] => {
+            let fuzzy = format!(
+                "space = {} AND title ~ {} AND type = page",
+                cql_string(space),
+                cql_string(title)
+            );
+            let mut data =
+                fetch_initial_page(&client, &url, &fuzzy, "", SEARCH_BODY_LIMIT, config).await?;
+            let candidates = extract_content_from_results(&mut data, false);
+            if candidates.is_empty() {
+                anyhow::bail!("No page titled {:?} in space {}", title, space);
+            }
+            anyhow::bail!(
+                "No page titled {:?} in space {}. Similar titles:\n{}",
+                title,
+                space,
+                describe_candidates(&candidates)
+            )
+        }
+        _ => anyhow::bail!(
+            "{} pages titled {:?} in space {}:\n{}",
+            matches.len(),
+            title,
+            space,
+            describe_candidates(&matches)
+        ),
+    }
+}
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+/// Quote a value as a CQL string literal
+fn cql_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// One `  <id>  <title>` line per page
+fn describe_candidates(pages: &[Value]) -> String {
+    pages
+        .iter()
+        .map(|page| {
+            format!(
+                "  {}  {}",
+                page_id_of(page).unwrap_or_default(),
+                page["title"].as_str().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Fetch a historical version of a page (body + version metadata)
+pub async fn get_page_version(
+    page_id: &str,
+    version: u32,
+    as_markdown: bool,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+    let url = page_url(page_id, config);
+
+    let mut query_params = if config.is_datacenter() {
+        vec![
+            ("status".to_string(), "historical".to_string()),
+            ("expand".to_string(), DC_PAGE_EXPAND.to_string()),
+        ]
+    } else {
+        apply_v2_filtering(None, None)
+    };
+    query_params.push(("version".to_string(), version.to_string()));
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&query_params),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to get child pages ({}): {}", status, body);
+        if matches!(status.as_u16(), 400 | 404)
+            && let Ok(latest) = get_latest_version_number(page_id, config).await
+        {
+            anyhow::bail!("{}", version_not_found_message(page_id, version, latest));
+        }
+        anyhow::bail!("Failed to get page version ({}): {}", status, body);
     }
 
     let mut data: Value = response.json().await?;
     filter::apply(&mut data, config);
 
-    Ok(json!({"items": data["results"]}))
+    if as_markdown {
+        convert_page_to_markdown(&mut data);
+    }
+
+    Ok(data)
 }
 
-pub async fn get_comments(page_id: &str, as_markdown: bool, config: &Config) -> Result<Value> {
+async fn get_latest_version_number(page_id: &str, config: &Config) -> Result<u64> {
     let client = http::client(config);
-    let url = format!(
-        "{}/wiki/api/v2/pages/{}/footer-comments",
-        config.base_url(),
-        page_id
-    );
+    let url = page_url(page_id, config);
+    // v2 returns the version by default, the Data Center content API only when expanded
+    let query: &[(&str, &str)] = if config.is_datacenter() {
+        &[("expand", "version")]
+    } else {
+        &[]
+    };
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .query(&[("body-format", "storage")])
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(query),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to get comments ({}): {}", status, body);
+        anyhow::bail!("Failed to get page ({})", status);
     }
 
-    let mut data: Value = response.json().await?;
-    filter::apply(&mut data, config);
+    let data: Value = response.json().await?;
+    data["version"]["number"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("Page {} has no version number", page_id))
+}
 
-    if as_markdown {
-        convert_comments_to_markdown(&mut data);
+fn version_not_found_message(page_id: &str, requested: u32, latest: u64) -> String {
+    format!(
+        "Version {} not found for page {} (valid versions: 1-{})",
+        requested, page_id, latest
+    )
+}
+
+pub async fn get_page_children(page_id: &str, config: &Config) -> Result<Value> {
+    let items = fetch_page_children(page_id, config).await?;
+    Ok(json!({"items": items}))
+}
+
+/// Walk the descendant tree of a page depth-first, returning nested `children` arrays.
+/// `max_depth` counts levels below `page_id` (1 = direct children only).
+pub async fn get_page_descendants(
+    page_id: &str,
+    max_depth: Option<u32>,
+    config: &Config,
+) -> Result<Value> {
+    let mut visited: HashSet<String> = HashSet::from([page_id.to_string()]);
+    let mut stack: Vec<(String, u32)> = vec![(page_id.to_string(), 0)];
+    let mut nodes: Vec<(String, Value)> = Vec::new();
+
+    while let Some((parent_id, depth)) = stack.pop() {
+        let children = fetch_page_children(&parent_id, config).await?;
+        let mut descend = Vec::new();
+        for mut child in children {
+            let Some(id) = page_id_of(&child) else {
+                continue;
+            };
+            // Guard against cycles (e.g. a page reported under more than one parent)
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            if let Some(obj) = child.as_object_mut() {
+                obj.insert("depth".to_string(), json!(depth + 1));
+            }
+            nodes.push((parent_id.clone(), child));
+            if max_depth.is_none_or(|max| depth + 1 < max) {
+                descend.push((id, depth + 1));
+            }
+        }
+        // Reverse so the first child is visited first
+        stack.extend(descend.into_iter().rev());
+    }
+
+    if !config.quiet {
+        eprintln!("Total: {} descendant pages fetched", nodes.len());
     }
 
-    Ok(json!({"items": data["results"]}))
+    Ok(json!({"items": build_page_tree(page_id, &mut nodes)}))
+}
+
+fn page_id_of(page: &Value) -> Option<String> {
+    match &page["id"] {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Nest `(parent_id, page)` pairs under `root_id`, preserving fetch order
+fn build_page_tree(root_id: &str, nodes: &mut Vec<(String, Value)>) -> Vec<Value> {
+    let (mine, rest): (Vec<_>, Vec<_>) = std::mem::take(nodes)
+        .into_iter()
+        .partition(|(parent, _)| parent == root_id);
+    *nodes = rest;
+
+    mine.into_iter()
+        .map(|(_, mut page)| {
+            let id = page_id_of(&page).unwrap_or_default();
+            let children = build_page_tree(&id, nodes);
+            if let Some(obj) = page.as_object_mut() {
+                obj.insert("children".to_string(), json!(children));
+            }
+            page
+        })
+        .collect()
+}
+
+async fn fetch_page_children(page_id: &str, config: &Config) -> Result<Vec<Value>> {
+    let client = http::client(config);
+    let url = if config.is_datacenter() {
+        format!("{}/child/page", page_url(page_id, config))
+    } else {
+        format!("{}/children", page_url(page_id, config))
+    };
+    let mut children = Value::Array(fetch_all_results(&client, &url, "child pages", config).await?);
+    filter::apply(&mut children, config);
+    Ok(serde_json::from_value(children)?)
+}
+
+/// Every `results` item of a list endpoint, following `_links.next` (the v2 `cursor`, or
+/// the `start` offset on Data Center) with the largest page size the API allows
+async fn fetch_all_results(
+    client: &Client,
+    url: &str,
+    what: &str,
+    config: &Config,
+) -> Result<Vec<Value>> {
+    let mut items: Vec<Value> = Vec::new();
+    let mut next: Option<(&str, String)> = None;
+    loop {
+        let mut query = vec![("limit", MAX_LIMIT.to_string())];
+        if let Some(param) = next.take() {
+            query.push(param);
+        }
+
+        let response = http::send_timed(
+            client
+                .get(url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&query),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get {} ({}): {}", what, status, body);
+        }
+
+        let data: Value = response.json().await?;
+        items.extend(data["results"].as_array().cloned().unwrap_or_default());
+
+        next = data["_links"]["next"].as_str().and_then(next_page_param);
+        if next.is_none() {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// The query parameter of a `_links.next` path that fetches the following page
+fn next_page_param(next_path: &str) -> Option<(&'static str, String)> {
+    match next_cursor(next_path) {
+        Some(cursor) => Some(("cursor", cursor)),
+        None => {
+            let url = reqwest::Url::parse("http://localhost")
+                .ok()?
+                .join(next_path)
+                .ok()?;
+            url.query_pairs()
+                .find(|(key, _)| key == "start")
+                .map(|(_, value)| ("start", value.into_owned()))
+        }
+    }
+}
+
+/// Footer comments of a page, or inline comments with `inline`. Only the first page is
+/// fetched unless `all`, which follows the v2 cursor.
+pub async fn get_comments(
+    page_id: &str,
+    inline: bool,
+    all: bool,
+    as_markdown: bool,
+    config: &Config,
+) -> Result<Value> {
+    ensure_cloud("comments", config)?;
+    let client = http::client(config);
+    let kind = if inline {
+        "inline-comments"
+    } else {
+        "footer-comments"
+    };
+    let url = format!("{}/api/v2/pages/{}/{}", config.wiki_url(), page_id, kind);
+
+    let mut items: Vec<Value> = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut query = vec![("body-format", "storage".to_string())];
+        if all {
+            query.push(("limit", MAX_LIMIT.to_string()));
+        }
+        if let Some(cursor) = &cursor {
+            query.push(("cursor", cursor.clone()));
+        }
+
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&query),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get comments ({}): {}", status, body);
+        }
+
+        let mut data: Value = response.json().await?;
+        // Read the cursor first: filtering strips `_links`
+        cursor = data["_links"]["next"].as_str().and_then(next_cursor);
+        filter::apply(&mut data, config);
+
+        if as_markdown {
+            convert_comments_to_markdown(&mut data);
+        }
+        items.extend(data["results"].as_array().cloned().unwrap_or_default());
+
+        if !all || cursor.is_none() {
+            break;
+        }
+    }
+
+    if inline {
+        items = items.iter().map(simplify_inline_comment).collect();
+    }
+    Ok(json!({"items": items}))
+}
+
+fn simplify_inline_comment(comment: &Value) -> Value {
+    json!({
+        "id": comment["id"],
+        "author": comment["version"]["authorId"],
+        "created": comment["version"]["createdAt"],
+        "resolution_status": comment["resolutionStatus"],
+        "selection": comment["properties"]["inlineOriginalSelection"],
+        "body": comment["body"]["storage"]["value"],
+    })
+}
+
+/// Every version of a page, following the cursor through all pages of history
+pub async fn get_versions(page_id: &str, config: &Config) -> Result<Value> {
+    ensure_cloud("versions", config)?;
+    let client = http::client(config);
+    let url = format!("{}/api/v2/pages/{}/versions", config.wiki_url(), page_id);
+
+    let mut versions: Vec<Value> = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut query = vec![("limit", MAX_LIMIT.to_string())];
+        if let Some(cursor) = &cursor {
+            query.push(("cursor", cursor.clone()));
+        }
+
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&query),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get page versions ({}): {}", status, body);
+        }
+
+        let data: Value = response.json().await?;
+        if let Some(results) = data["results"].as_array() {
+            versions.extend(results.iter().map(simplify_page_version));
+        }
+
+        cursor = data["_links"]["next"].as_str().and_then(next_cursor);
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(json!({
+        "page_id": page_id,
+        "versions": versions,
+        "count": versions.len()
+    }))
+}
+
+fn simplify_page_version(version: &Value) -> Value {
+    json!({
+        "number": version.get("number").cloned().unwrap_or(Value::Null),
+        "authorId": version.get("authorId").cloned().unwrap_or(Value::Null),
+        "createdAt": version.get("createdAt").cloned().unwrap_or(Value::Null),
+        "message": version.get("message").cloned().unwrap_or(Value::Null),
+    })
+}
+
+/// v2 attachments carry these at the top level; the Data Center content API nests them
+fn simplify_attachment(attachment: &Value) -> Value {
+    let field = |v2: &str, dc: &Value| {
+        attachment
+            .get(v2)
+            .or_else(|| Some(dc).filter(|v| !v.is_null()))
+            .cloned()
+            .unwrap_or(Value::Null)
+    };
+    json!({
+        "id": attachment.get("id").cloned().unwrap_or(Value::Null),
+        "title": attachment.get("title").cloned().unwrap_or(Value::Null),
+        "mediaType": field("mediaType", &attachment["metadata"]["mediaType"]),
+        "fileSize": field("fileSize", &attachment["extensions"]["fileSize"]),
+        "downloadLink": field("downloadLink", &attachment["_links"]["download"]),
+    })
+}
+
+pub async fn get_attachments(page_id: &str, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = if config.is_datacenter() {
+        format!("{}/child/attachment", page_url(page_id, config))
+    } else {
+        format!("{}/attachments", page_url(page_id, config))
+    };
+
+    let items: Vec<Value> = fetch_all_results(&client, &url, "attachments", config)
+        .await?
+        .iter()
+        .map(simplify_attachment)
+        .collect();
+
+    Ok(json!({
+        "items": items,
+        "count": items.len()
+    }))
+}
+
+pub async fn download_attachment(
+    attachment_id: &str,
+    output_path: Option<&Path>,
+    overwrite: bool,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+
+    // First, get attachment metadata to get the download link and filename
+    let meta_url = if config.is_datacenter() {
+        page_url(attachment_id, config)
+    } else {
+        format!("{}/api/v2/attachments/{}", config.wiki_url(), attachment_id)
+    };
+
+    let meta_response = http::send_timed(
+        client
+            .get(&meta_url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !meta_response.status().is_success() {
+        let status = meta_response.status();
+        let body = meta_response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get attachment metadata ({}): {}", status, body);
+    }
+
+    let metadata: Value = meta_response.json().await?;
+    let download_link = metadata["downloadLink"]
+        .as_str()
+        .or_else(|| metadata["_links"]["download"].as_str())
+        .ok_or_else(|| anyhow::anyhow!("No download link in attachment metadata"))?;
+    let filename = metadata["title"].as_str().unwrap_or("attachment");
+    let final_path = attachment::resolve_output_path(output_path, filename, overwrite)?;
+
+    // downloadLink is relative to the Confluence context path (/wiki on Cloud)
+    let content_url = build_download_url(&config.wiki_url(), download_link);
+
+    let content_response = http::send_timed(
+        client
+            .get(&content_url)
+            .header("Authorization", http::auth_header(config)),
+    )
+    .await?;
+
+    if !content_response.status().is_success() {
+        let status = content_response.status();
+        let body = content_response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to download attachment ({}): {}", status, body);
+    }
+
+    let bytes = content_response.bytes().await?;
+    std::fs::write(&final_path, &bytes)?;
+
+    Ok(json!({
+        "filename": filename,
+        "path": final_path.to_string_lossy(),
+        "size": bytes.len(),
+        "id": attachment_id
+    }))
+}
+
+fn build_download_url(wiki_url: &str, download_link: &str) -> String {
+    if download_link.starts_with("http") {
+        download_link.to_string()
+    } else {
+        format!("{}{}", wiki_url, download_link)
+    }
 }
 
 pub async fn create_page(
     space_key: &str,
     title: &str,
     content: &str,
+    parent_id: Option<&str>,
     include_all_fields: Option<bool>,
     additional_includes: Option<Vec<String>>,
     config: &Config,
 ) -> Result<Value> {
     let client = http::client(config);
 
+    if config.is_datacenter() {
+        let url = format!("{}/rest/api/content", config.wiki_url());
+        let body = dc_create_body(space_key, title, content, parent_id);
+        return send_page_create(http::no_check(client.post(&url), config), &body, config).await;
+    }
+
     // First, convert space_key to space_id using v2 API
-    let space_url = format!("{}/wiki/api/v2/spaces", config.base_url());
-
-    let space_response = client
-        .get(&space_url)
-        .query(&[("keys", space_key)]) // Automatic URL encoding
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+    let space_url = format!("{}/api/v2/spaces", config.wiki_url());
+
+    let space_response = http::send_timed(
+        client
+            .get(&space_url)
+            .query(&[("keys", space_key)]) // Automatic URL encoding
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
 
     if !space_response.status().is_success() {
         let status = space_response.status();
@@ -341,11 +988,11 @@ pub async fn create_page(
         .ok_or_else(|| anyhow::anyhow!("Space '{}' not found", space_key))?;
 
     // Now create the page with v2 API
-    let url = format!("{}/wiki/api/v2/pages", config.base_url());
+    let url = format!("{}/api/v2/pages", config.wiki_url());
 
     let query_params = apply_v2_filtering(include_all_fields, additional_includes);
 
-    let body = json!({
+    let mut body = json!({
         "spaceId": space_id,
         "title": title,
         "body": {
@@ -353,15 +1000,26 @@ pub async fn create_page(
             "value": content
         }
     });
+    if let Some(parent_id) = parent_id {
+        body["parentId"] = json!(parent_id);
+    }
 
-    let response = client
-        .post(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .query(&query_params)
-        .json(&body)
-        .send()
-        .await?;
+    let request = http::no_check(client.post(&url), config).query(&query_params);
+    send_page_create(request, &body, config).await
+}
+
+async fn send_page_create(
+    request: reqwest::RequestBuilder,
+    body: &Value,
+    config: &Config,
+) -> Result<Value> {
+    let response = http::send_timed(
+        request
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -376,26 +1034,54 @@ pub async fn create_page(
     }))
 }
 
+/// Data Center's content API takes the space key and the parent as an ancestor
+fn dc_create_body(space_key: &str, title: &str, content: &str, parent_id: Option<&str>) -> Value {
+    let mut body = json!({
+        "type": "page",
+        "title": title,
+        "space": {"key": space_key},
+        "body": {
+            "storage": {"value": content, "representation": "storage"}
+        }
+    });
+    if let Some(parent_id) = parent_id {
+        body["ancestors"] = json!([{"id": parent_id}]);
+    }
+    body
+}
+
+/// Update a page's title and/or storage body; whichever is omitted is carried over
+/// from the current version so a body edit can't accidentally rename the page.
 pub async fn update_page(
     page_id: &str,
-    title: &str,
-    content: &str,
+    title: Option<&str>,
+    content: Option<&str>,
     include_all_fields: Option<bool>,
     additional_includes: Option<Vec<String>>,
     config: &Config,
 ) -> Result<Value> {
+    if title.is_none() && content.is_none() {
+        anyhow::bail!("Nothing to update: pass a title, content, or both");
+    }
     let client = http::client(config);
 
     // First, get the current page to get the version number using v2 API
-    let get_url = format!("{}/wiki/api/v2/pages/{}", config.base_url(), page_id);
-
-    let get_response = client
-        .get(&get_url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .query(&[("include-version", "true")])
-        .send()
-        .await?;
+    let get_url = page_url(page_id, config);
+    let get_query = match (config.is_datacenter(), content.is_none()) {
+        (true, true) => vec![("expand", DC_PAGE_EXPAND)],
+        (true, false) => vec![("expand", "version")],
+        (false, true) => vec![("include-version", "true"), ("body-format", "storage")],
+        (false, false) => vec![("include-version", "true")],
+    };
+
+    let get_response = http::send_timed(
+        client
+            .get(&get_url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&get_query),
+    )
+    .await?;
 
     if !get_response.status().is_success() {
         let status = get_response.status();
@@ -407,32 +1093,61 @@ pub async fn update_page(
     let current_version = current_page["version"]["number"]
         .as_u64()
         .ok_or_else(|| anyhow::anyhow!("Failed to get current version"))?;
+    let title = match title {
+        Some(title) => title,
+        None => current_page["title"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get current title"))?,
+    };
+    let content = match content {
+        Some(content) => content,
+        None => current_page["body"]["storage"]["value"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get current body"))?,
+    };
 
-    // Now update the page with v2 API
-    let update_url = format!("{}/wiki/api/v2/pages/{}", config.base_url(), page_id);
+    // Now update the page with v2 API (the content API on Data Center)
+    let update_url = page_url(page_id, config);
 
-    let query_params = apply_v2_filtering(include_all_fields, additional_includes);
-
-    let body = json!({
-        "id": page_id,
-        "title": title,
-        "body": {
-            "representation": "storage",
-            "value": content
-        },
-        "version": {
-            "number": current_version + 1
-        }
-    });
+    let (query_params, body) = if config.is_datacenter() {
+        let body = json!({
+            "id": page_id,
+            "type": "page",
+            "title": title,
+            "body": {
+                "storage": {"value": content, "representation": "storage"}
+            },
+            "version": {
+                "number": current_version + 1
+            }
+        });
+        (Vec::new(), body)
+    } else {
+        let body = json!({
+            "id": page_id,
+            "title": title,
+            "body": {
+                "representation": "storage",
+                "value": content
+            },
+            "version": {
+                "number": current_version + 1
+            }
+        });
+        (
+            apply_v2_filtering(include_all_fields, additional_includes),
+            body,
+        )
+    };
 
-    let response = client
-        .put(&update_url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .query(&query_params)
-        .json(&body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.put(&update_url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .query(&query_params)
+            .json(&body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -486,6 +1201,20 @@ fn convert_page_to_markdown(data: &mut Value) {
     }
 }
 
+/// Replace the storage body with plain text (for `--format text`)
+pub fn convert_page_to_text(data: &mut Value) {
+    let Some(body) = data
+        .get_mut("body")
+        .and_then(|b| b.get_mut("storage"))
+        .and_then(|s| s.get_mut("value"))
+    else {
+        return;
+    };
+    if let Some(html) = body.as_str().map(|s| s.to_string()) {
+        *body = Value::String(confluence_to_text(&html));
+    }
+}
+
 fn convert_comments_to_markdown(data: &mut Value) {
     let Some(results) = data.get_mut("results").and_then(|r| r.as_array_mut()) else {
         return;
@@ -507,7 +1236,8 @@ fn convert_comments_to_markdown(data: &mut Value) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::create_test_config_with_filters;
+    use crate::config::Deployment;
+    use crate::test_utils::{create_test_config_with_filters, serve, serve_once, use_stub};
 
     fn create_test_config(confluence_spaces_filter: Vec<String>) -> Config {
         create_test_config_with_filters(vec![], confluence_spaces_filter)
@@ -518,12 +1248,6 @@ mod tests {
         assert_eq!(MAX_LIMIT, 250);
     }
 
-    #[test]
-    fn test_rate_limit_delay_default() {
-        let config = create_test_config(vec![]);
-        assert_eq!(config.performance.rate_limit_delay_ms, 200);
-    }
-
     #[test]
     fn test_apply_space_filter_injection() {
         let config = create_test_config(vec!["SPACE1".to_string(), "SPACE2".to_string()]);
@@ -545,6 +1269,122 @@ mod tests {
         assert_eq!(result, "type = page");
     }
 
+    #[test]
+    fn test_apply_type_filter_injection() {
+        let result = apply_type_filter("text ~ \"release\"", Some("page"));
+        assert_eq!(result, "type = page AND (text ~ \"release\")");
+    }
+
+    #[test]
+    fn test_apply_type_filter_not_injected_when_present() {
+        for cql in [
+            "type = blogpost AND text ~ x",
+            "TYPE=page",
+            "type IN (page, blogpost)",
+            "type not in (comment)",
+        ] {
+            assert_eq!(apply_type_filter(cql, Some("page")), cql);
+        }
+        // Other fields ending in "type" are not a type clause
+        assert_eq!(
+            apply_type_filter("mediatype = \"image/png\"", Some("attachment")),
+            "type = attachment AND (mediatype = \"image/png\")"
+        );
+    }
+
+    #[test]
+    fn test_apply_type_filter_ignores_quoted_type() {
+        assert_eq!(
+            apply_type_filter("text ~ \"type = page\"", Some("blogpost")),
+            "type = blogpost AND (text ~ \"type = page\")"
+        );
+        assert_eq!(
+            apply_type_filter("title ~ 'say \\'type in\\''", Some("page")),
+            "type = page AND (title ~ 'say \\'type in\\'')"
+        );
+        // A real clause next to a quoted one is still recognized
+        let cql = "text ~ \"x\" AND type = page";
+        assert_eq!(apply_type_filter(cql, Some("blogpost")), cql);
+    }
+
+    #[test]
+    fn test_apply_type_filter_none() {
+        assert_eq!(apply_type_filter("text ~ x", None), "text ~ x");
+    }
+
+    #[test]
+    fn test_type_and_space_filters_compose() {
+        let config = create_test_config(vec!["SPACE1".to_string()]);
+        let result = apply_space_filter(&apply_type_filter("text ~ x", Some("page")), &config);
+        assert_eq!(
+            result,
+            "space IN (\"SPACE1\") AND (type = page AND (text ~ x))"
+        );
+    }
+
+    #[test]
+    fn test_cql_string_escapes_quotes() {
+        assert_eq!(cql_string("Release notes"), "\"Release notes\"");
+        assert_eq!(cql_string(r#"Say "hi" \ bye"#), r#""Say \"hi\" \\ bye""#);
+        assert!(validate_cql(&format!("title = {}", cql_string(r#"a "(" b"#))).is_ok());
+    }
+
+    #[test]
+    fn test_describe_candidates() {
+        let pages = vec![
+            json!({"id": "111", "title": "Runbook"}),
+            json!({"id": "222", "title": "Runbook (old)"}),
+        ];
+        assert_eq!(
+            describe_candidates(&pages),
+            "  111  Runbook\n  222  Runbook (old)"
+        );
+    }
+
+    #[test]
+    fn test_simplify_inline_comment() {
+        let comment = json!({
+            "id": "42",
+            "status": "current",
+            "resolutionStatus": "open",
+            "version": {"authorId": "abc123", "createdAt": "2024-05-01T10:00:00.000Z", "number": 1},
+            "properties": {"inlineOriginalSelection": "the quoted text", "inlineMarkerRef": "m1"},
+            "body": {"storage": {"value": "Typo here", "representation": "storage"}}
+        });
+        assert_eq!(
+            simplify_inline_comment(&comment),
+            json!({
+                "id": "42",
+                "author": "abc123",
+                "created": "2024-05-01T10:00:00.000Z",
+                "resolution_status": "open",
+                "selection": "the quoted text",
+                "body": "Typo here"
+            })
+        );
+    }
+
+    #[test]
+    fn test_simplify_page_version() {
+        let version = json!({
+            "number": 3,
+            "authorId": "abc123",
+            "createdAt": "2024-05-01T10:00:00.000Z",
+            "message": "Fix typo",
+            "minorEdit": false,
+            "page": {"id": "12345"}
+        });
+        assert_eq!(
+            simplify_page_version(&version),
+            json!({
+                "number": 3,
+                "authorId": "abc123",
+                "createdAt": "2024-05-01T10:00:00.000Z",
+                "message": "Fix typo"
+            })
+        );
+    }
+
     #[test]
     fn test_build_next_url_relative_path() {
         // _links.base from API includes /wiki, _links.next does NOT include /wiki
@@ -568,30 +1408,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_project_fields() {
+        let mut result = json!({
+            "items": [
+                {"id": "1", "title": "A", "body": {"storage": {"value": "<p>big</p>"}}, "space": "TEAM"},
+                {"id": "2", "title": "B"}
+            ],
+            "total": 2
+        });
+        project_fields(&mut result, &["id".to_string(), "title".to_string()]);
+        assert_eq!(
+            result,
+            json!({
+                "items": [{"id": "1", "title": "A"}, {"id": "2", "title": "B"}],
+                "total": 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_next_cursor() {
+        assert_eq!(
+            next_cursor("/rest/api/search?cql=type%3Dpage&cursor=abc%3D123&next=true"),
+            Some("abc=123".to_string())
+        );
+        assert_eq!(next_cursor("/rest/api/search?cql=type%3Dpage"), None);
+    }
+
+    #[test]
+    fn test_build_download_url_relative() {
+        let result = build_download_url(
+            &create_test_config(vec![]).wiki_url(),
+            "/download/attachments/123/report.pdf?version=1",
+        );
+        assert_eq!(
+            result,
+            "https://test.atlassian.net/wiki/download/attachments/123/report.pdf?version=1"
+        );
+    }
+
+    #[test]
+    fn test_build_download_url_absolute() {
+        let link = "https://cdn.example.com/report.pdf";
+        assert_eq!(build_download_url("https://test.atlassian.net", link), link);
+    }
+
+    #[test]
+    fn test_simplify_attachment() {
+        let attachment = json!({
+            "id": "att123",
+            "title": "report.pdf",
+            "mediaType": "application/pdf",
+            "fileSize": 1024,
+            "downloadLink": "/download/attachments/1/report.pdf",
+            "_links": {"webui": "/x"}
+        });
+        let simplified = simplify_attachment(&attachment);
+        assert_eq!(simplified["id"], "att123");
+        assert_eq!(simplified["title"], "report.pdf");
+        assert_eq!(simplified["fileSize"], 1024);
+        assert!(simplified.get("_links").is_none());
+    }
+
     // T018: Remaining Confluence handlers tests
 
+    #[test]
+    fn test_validate_cql_ok() {
+        assert!(validate_cql("type = page AND (space = DEV OR title ~ \"a (b\")").is_ok());
+        assert!(validate_cql("title ~ 'it\\'s'").is_ok());
+    }
+
+    #[test]
+    fn test_validate_cql_empty() {
+        let err = validate_cql("   ").unwrap_err();
+        assert_eq!(err.to_string(), "CQL query is empty");
+    }
+
+    #[test]
+    fn test_empty_cql_with_spaces_filter() {
+        let config = create_test_config(vec!["SPACE1".to_string()]);
+        let result = apply_space_filter("  ", &config);
+        assert_eq!(result, "space IN (\"SPACE1\")");
+        assert!(validate_cql(&result).is_ok());
+        assert_eq!(apply_type_filter("", Some("page")), "type = page");
+    }
+
+    #[test]
+    fn test_validate_cql_unterminated_quote() {
+        let err = validate_cql("title = \"Release notes").unwrap_err();
+        assert!(err.to_string().contains("unterminated \" quote"));
+    }
+
+    #[test]
+    fn test_validate_cql_unbalanced_parens() {
+        assert!(
+            validate_cql("(type = page AND space = DEV")
+                .unwrap_err()
+                .to_string()
+                .contains("unclosed '('")
+        );
+        assert!(
+            validate_cql("type = page)")
+                .unwrap_err()
+                .to_string()
+                .contains("unexpected ')' at position 12")
+        );
+    }
+
     // get_page tests
     #[test]
     fn test_get_page_url_construction() {
         let config = create_test_config(vec![]);
         let page_id = "12345";
 
-        let url = format!("{}/wiki/api/v2/pages/{}", config.base_url(), page_id);
+        let url = format!("{}/api/v2/pages/{}", config.wiki_url(), page_id);
 
         assert_eq!(url, "https://test.atlassian.net/wiki/api/v2/pages/12345");
     }
 
+    #[test]
+    fn test_version_not_found_message() {
+        assert_eq!(
+            version_not_found_message("12345", 9, 4),
+            "Version 9 not found for page 12345 (valid versions: 1-4)"
+        );
+    }
+
     // get_page_children tests
+    #[test]
+    fn test_build_page_tree_nests_children() {
+        let mut nodes = vec![
+            (
+                "1".to_string(),
+                json!({"id": "2", "title": "A", "depth": 1}),
+            ),
+            (
+                "2".to_string(),
+                json!({"id": "3", "title": "A.1", "depth": 2}),
+            ),
+            (
+                "1".to_string(),
+                json!({"id": "4", "title": "B", "depth": 1}),
+            ),
+        ];
+        let tree = build_page_tree("1", &mut nodes);
+
+        assert!(nodes.is_empty());
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0]["title"], "A");
+        assert_eq!(tree[0]["children"][0]["title"], "A.1");
+        assert_eq!(tree[0]["children"][0]["children"], json!([]));
+        assert_eq!(tree[1]["title"], "B");
+        assert_eq!(tree[1]["children"], json!([]));
+    }
+
+    #[test]
+    fn test_page_id_of_accepts_string_and_number() {
+        assert_eq!(page_id_of(&json!({"id": "12"})), Some("12".to_string()));
+        assert_eq!(page_id_of(&json!({"id": 12})), Some("12".to_string()));
+        assert_eq!(page_id_of(&json!({})), None);
+    }
+
     #[test]
     fn test_get_page_children_url_construction() {
         let config = create_test_config(vec![]);
         let page_id = "12345";
 
-        let url = format!(
-            "{}/wiki/api/v2/pages/{}/children",
-            config.base_url(),
-            page_id
-        );
+        let url = format!("{}/api/v2/pages/{}/children", config.wiki_url(), page_id);
 
         assert_eq!(
             url,
@@ -599,6 +1583,188 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_fetch_page_children_follows_cursor() {
+        let (base_url, server) = serve(vec![
+            (
+                "200 OK",
+                r#"{"results":[{"id":"2"}],"_links":{"next":"/wiki/api/v2/pages/1/children?cursor=c2"}}"#,
+            ),
+            ("200 OK", r#"{"results":[{"id":"3"}],"_links":{}}"#),
+        ]);
+
+        let mut config = create_test_config(vec![]);
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+
+        let children = fetch_page_children("1", &config).await.unwrap();
+        assert_eq!(children, vec![json!({"id": "2"}), json!({"id": "3"})]);
+
+        let requests = server.join().unwrap().requests;
+        assert!(
+            requests[0]
+                .line
+                .contains("/wiki/api/v2/pages/1/children?limit=250 ")
+        );
+        assert!(requests[1].line.contains("limit=250&cursor=c2"));
+    }
+
+    #[tokio::test]
+    async fn test_get_comments_all_follows_cursor() {
+        let (base_url, server) = serve(vec![
+            (
+                "200 OK",
+                r#"{"results":[{"id":"c1"}],"_links":{"next":"/wiki/api/v2/pages/1/footer-comments?cursor=n2"}}"#,
+            ),
+            ("200 OK", r#"{"results":[{"id":"c2"}]}"#),
+        ]);
+
+        let mut config = create_test_config(vec![]);
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+
+        let result = get_comments("1", false, true, false, &config)
+            .await
+            .unwrap();
+        assert_eq!(result["items"], json!([{"id": "c1"}, {"id": "c2"}]));
+        assert!(
+            server.join().unwrap().requests[1]
+                .line
+                .contains("cursor=n2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_attachments_follows_cursor() {
+        let (base_url, server) = serve(vec![
+            (
+                "200 OK",
+                r#"{"results":[{"id":"att1","title":"a.pdf","fileSize":10}],"_links":{"next":"/wiki/api/v2/pages/1/attachments?cursor=c2"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"results":[{"id":"att2","title":"b.png","mediaType":"image/png"}]}"#,
+            ),
+        ]);
+
+        let mut config = create_test_config(vec![]);
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+
+        let result = get_attachments("1", &config).await.unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["items"][1]["title"], "b.png");
+        assert_eq!(result["items"][1]["mediaType"], "image/png");
+        assert!(
+            server.join().unwrap().requests[1]
+                .line
+                .contains("cursor=c2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_page_datacenter_uses_content_api() {
+        let (base_url, server) = serve_once(
+            "200 OK",
+            r#"{"id":"123","title":"Home","version":{"number":4},"body":{"storage":{"value":"<p>Hi</p>","representation":"storage"}}}"#,
+        );
+
+        let mut config = create_test_config(vec![]);
+        config.deployment = Deployment::DataCenter;
+        use_stub(&mut config, &base_url);
+
+        let page = get_page("123", None, None, true, &config).await.unwrap();
+        assert_eq!(page["body"]["storage"]["value"], "Hi");
+
+        let request = server.join().unwrap();
+        assert!(
+            request
+                .line
+                .contains("/rest/api/content/123?expand=body.storage%2Cversion "),
+            "{}",
+            request.line
+        );
+        assert!(!request.line.contains("/api/v2/"));
+    }
+
+    #[tokio::test]
+    async fn test_datacenter_children_follow_start_offset() {
+        let (base_url, server) = serve(vec![
+            (
+                "200 OK",
+                r#"{"results":[{"id":"2"}],"_links":{"next":"/rest/api/content/1/child/page?limit=250&start=250"}}"#,
+            ),
+            ("200 OK", r#"{"results":[{"id":"3"}],"_links":{}}"#),
+        ]);
+
+        let mut config = create_test_config(vec![]);
+        config.deployment = Deployment::DataCenter;
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+
+        let children = fetch_page_children("1", &config).await.unwrap();
+        assert_eq!(children.len(), 2);
+
+        let requests = server.join().unwrap().requests;
+        assert!(
+            requests[0]
+                .line
+                .contains("/rest/api/content/1/child/page?limit=250 ")
+        );
+        assert!(requests[1].line.contains("limit=250&start=250"));
+    }
+
+    #[test]
+    fn test_dc_create_body() {
+        let body = dc_create_body("DOCS", "Title", "<p>x</p>", Some("42"));
+        assert_eq!(
+            body,
+            json!({
+                "type": "page",
+                "title": "Title",
+                "space": {"key": "DOCS"},
+                "body": {"storage": {"value": "<p>x</p>", "representation": "storage"}},
+                "ancestors": [{"id": "42"}]
+            })
+        );
+        assert!(
+            dc_create_body("DOCS", "T", "", None)
+                .get("ancestors")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_simplify_attachment_datacenter_shape() {
+        let attachment = json!({
+            "id": "att9",
+            "title": "a.pdf",
+            "metadata": {"mediaType": "application/pdf"},
+            "extensions": {"fileSize": 1024},
+            "_links": {"download": "/download/attachments/1/a.pdf"}
+        });
+        assert_eq!(
+            simplify_attachment(&attachment),
+            json!({
+                "id": "att9",
+                "title": "a.pdf",
+                "mediaType": "application/pdf",
+                "fileSize": 1024,
+                "downloadLink": "/download/attachments/1/a.pdf"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_comments_unsupported_on_datacenter() {
+        let mut config = create_test_config(vec![]);
+        config.deployment = Deployment::DataCenter;
+        let err = get_comments("1", false, false, false, &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported on Data Center"));
+    }
+
     // get_comments tests
     #[test]
     fn test_get_comments_url_construction() {
@@ -606,8 +1772,8 @@ mod tests {
         let page_id = "12345";
 
         let url = format!(
-            "{}/wiki/api/v2/pages/{}/footer-comments",
-            config.base_url(),
+            "{}/api/v2/pages/{}/footer-comments",
+            config.wiki_url(),
             page_id
         );
 
@@ -640,6 +1806,15 @@ mod tests {
     }
 
     // update_page tests
+    #[tokio::test]
+    async fn test_update_page_requires_title_or_content() {
+        let config = create_test_config(vec![]);
+        let err = update_page("12345", None, None, None, None, &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Nothing to update"));
+    }
+
     #[test]
     fn test_update_page_body_format() {
         let page_id = "12345";
@@ -0,0 +1,189 @@
+use regex::Regex;
+use serde_json::{Value, json};
+use std::fmt;
+use std::sync::LazyLock;
+
+/// `Failed to get issue (404 Not Found): ...` / `Search failed (400 Bad Request): ...`,
+/// i.e. the `(NNN Reason)` form of reqwest's `StatusCode` Display, not any number in parens
+static STATUS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(([1-5]\d{2}) [A-Z][^)]*\)").unwrap());
+
+/// Error classification used at the CLI boundary (`--errors-json` and exit codes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// Failed HTTP request; `status` is `None` for network errors (timeout, DNS, TLS)
+    Http {
+        status: Option<u16>,
+        message: String,
+    },
+    Config(String),
+    Io(String),
+    Other(String),
+}
+
+impl AppError {
+    /// Classify an error chain. An `AppError` anywhere in the chain wins; otherwise
+    /// reqwest/io/toml sources and `(<status> ...)` API messages are recognized.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        for cause in error.chain() {
+            if let Some(app) = cause.downcast_ref::<AppError>() {
+                return app.clone();
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                return AppError::Http {
+                    status: e.status().map(|s| s.as_u16()),
+                    message,
+                };
+            }
+            if cause.is::<std::io::Error>() {
+                return AppError::Io(message);
+            }
+            if cause.is::<toml::de::Error>() {
+                return AppError::Config(message);
+            }
+        }
+
+        match STATUS_RE
+            .captures(&message)
+            .and_then(|c| c[1].parse::<u16>().ok())
+        {
+            Some(status) => AppError::Http {
+                status: Some(status),
+                message,
+            },
+            None => AppError::Other(message),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Http { .. } => "http",
+            AppError::Config(_) => "config",
+            AppError::Io(_) => "io",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            AppError::Http { status, .. } => *status,
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Http { message, .. }
+            | AppError::Config(message)
+            | AppError::Io(message)
+            | AppError::Other(message) => message,
+        }
+    }
+
+    pub fn with_message(self, message: String) -> Self {
+        match self {
+            AppError::Http { status, .. } => AppError::Http { status, message },
+            AppError::Config(_) => AppError::Config(message),
+            AppError::Io(_) => AppError::Io(message),
+            AppError::Other(_) => AppError::Other(message),
+        }
+    }
+
+    /// Process exit code: 1 other, 3 config, 4 http, 5 io (2 is clap's usage error and
+    /// `--fail-on-empty` with no matches)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Other(_) => 1,
+            AppError::Config(_) => 3,
+            AppError::Http { .. } => 4,
+            AppError::Io(_) => 5,
+        }
+    }
+
+    /// `{"error": {"kind": ..., "status": ..., "message": ...}}`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "kind": self.kind(),
+                "status": self.status(),
+                "message": self.message(),
+            }
+        })
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_api_status_message() {
+        let error = anyhow::anyhow!("Failed to get issue (404 Not Found): missing");
+        let app = AppError::classify(&error);
+        assert_eq!(app.kind(), "http");
+        assert_eq!(app.status(), Some(404));
+        assert_eq!(app.exit_code(), 4);
+    }
+
+    #[test]
+    fn test_classify_ignores_other_parenthesised_numbers() {
+        for message in [
+            "Failed to parse line (123 chars)",
+            "Skipped (404)",
+            "Took too long (999 Seconds)",
+        ] {
+            let app = AppError::classify(&anyhow::anyhow!("{}", message));
+            assert_eq!(app.kind(), "other", "{}", message);
+            assert_eq!(app.exit_code(), 1);
+        }
+    }
+
+    #[test]
+    fn test_classify_io_error() {
+        let error = anyhow::Error::new(std::io::Error::other("disk full")).context("write");
+        let app = AppError::classify(&error);
+        assert_eq!(app.kind(), "io");
+        assert_eq!(app.message(), "write: disk full");
+    }
+
+    #[test]
+    fn test_classify_keeps_app_error() {
+        let error = anyhow::Error::new(AppError::Config("Domain not configured".to_string()));
+        assert_eq!(
+            AppError::classify(&error),
+            AppError::Config("Domain not configured".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classify_other() {
+        let app = AppError::classify(&anyhow::anyhow!("something odd"));
+        assert_eq!(app.kind(), "other");
+        assert_eq!(app.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_to_json_envelope() {
+        let app = AppError::Http {
+            status: Some(404),
+            message: "not found".to_string(),
+        };
+        assert_eq!(
+            app.to_json(),
+            json!({"error": {"kind": "http", "status": 404, "message": "not found"}})
+        );
+        assert_eq!(
+            AppError::Io("x".to_string()).to_json()["error"]["status"],
+            Value::Null
+        );
+    }
+}
@@ -41,7 +41,14 @@ pub const DEFAULT_EXCLUDE_FIELDS: &[&str] = &[
     "extensions",
 ];
 
+/// Strip excluded keys and empty strings at every nesting level, so a name like
+/// `customfield_10015` is also removed from `issues[].fields`.
+/// `optimization.response_exclude_fields` replaces DEFAULT_EXCLUDE_FIELDS when set.
+/// Nothing is stripped with `--raw`.
 pub fn apply(value: &mut Value, config: &crate::config::Config) {
+    if config.raw {
+        return;
+    }
     let exclude_fields = config
         .optimization
         .response_exclude_fields
@@ -99,6 +106,21 @@ mod tests {
         assert!(!obj.contains_key("self"));
     }
 
+    #[test]
+    fn test_raw_keeps_everything() {
+        let mut config = create_test_config();
+        config.raw = true;
+        let mut data = json!({
+            "name": "",
+            "avatarUrls": {"16x16": "url"},
+            "self": "https://api"
+        });
+        let original = data.clone();
+
+        apply(&mut data, &config);
+        assert_eq!(data, original);
+    }
+
     #[test]
     fn test_remove_empty_strings() {
         let config = create_test_config();
@@ -130,4 +152,30 @@ mod tests {
         assert!(!issues[0].as_object().unwrap().contains_key("self"));
         assert!(!issues[1].as_object().unwrap().contains_key("self"));
     }
+
+    #[test]
+    fn test_config_excludes_nested_custom_field() {
+        let mut config = create_test_config();
+        config.optimization.response_exclude_fields =
+            Some(vec!["customfield_10015".to_string(), "comment".to_string()]);
+        let mut data = json!({
+            "issues": [{
+                "key": "P-1",
+                "self": "url1",
+                "fields": {
+                    "summary": "Title",
+                    "customfield_10015": {"value": "heavy"},
+                    "comment": {"comments": []}
+                }
+            }]
+        });
+
+        apply(&mut data, &config);
+        let fields = data["issues"][0]["fields"].as_object().unwrap();
+        assert!(fields.contains_key("summary"));
+        assert!(!fields.contains_key("customfield_10015"));
+        assert!(!fields.contains_key("comment"));
+        // Configured list replaces the defaults
+        assert!(data["issues"][0].as_object().unwrap().contains_key("self"));
+    }
 }
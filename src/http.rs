@@ -1,24 +1,469 @@
-use crate::config::Config;
-use reqwest::Client;
-use std::time::Duration;
+use crate::config::{AuthType, Config};
+use anyhow::{Context, Result};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, NoProxy, Proxy, RequestBuilder, Response};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
+/// Process-wide limiter acquired by `send_timed`; its rate follows the config last
+/// passed to `client()`.
+static RATE_LIMITER: LazyLock<Mutex<TokenBucket>> =
+    LazyLock::new(|| Mutex::new(TokenBucket::new(0.0, Instant::now())));
+
+/// Directory set by `--dump`; every exchange is written there when present
+static DUMP_DIR: OnceLock<PathBuf> = OnceLock::new();
+static DUMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Bodies longer than this are truncated in dump files
+const DUMP_BODY_LIMIT: usize = 64 * 1024;
+
+/// Token bucket holding up to one second's worth of requests
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: Self::capacity_for(rate),
+            last_refill: now,
+        }
+    }
+
+    fn capacity_for(rate: f64) -> f64 {
+        rate.max(1.0)
+    }
+
+    fn set_rate(&mut self, rate: f64) {
+        if self.rate != rate {
+            self.rate = rate;
+            self.tokens = self.tokens.min(Self::capacity_for(rate));
+        }
+    }
+
+    /// Take a token, or return how long to wait before one is available
+    fn try_acquire(&mut self, now: Instant) -> Option<Duration> {
+        if self.rate <= 0.0 {
+            return None;
+        }
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(Self::capacity_for(self.rate));
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+async fn acquire_rate_limit() {
+    loop {
+        let wait = RATE_LIMITER
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .try_acquire(Instant::now());
+        match wait {
+            Some(wait) => tokio::time::sleep(wait).await,
+            None => return,
+        }
+    }
+}
+
+/// The config's shared client, built on first use. Clones share one connection pool,
+/// so calling this per request is cheap.
 pub fn client(config: &Config) -> Client {
-    Client::builder()
-        .timeout(Duration::from_millis(config.performance.request_timeout_ms))
-        .build()
-        .expect("Failed to create HTTP client")
+    RATE_LIMITER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .set_rate(config.performance.requests_per_second);
+
+    config
+        .http_client
+        .get_or_init(|| build_client(config))
+        .clone()
+}
+
+fn build_client(config: &Config) -> Client {
+    let mut builder =
+        Client::builder().timeout(Duration::from_millis(config.performance.request_timeout_ms));
+    // Without an explicit proxy, reqwest picks up HTTPS_PROXY/HTTP_PROXY/NO_PROXY itself
+    if let Some(proxy) = proxy(config) {
+        builder = builder.proxy(proxy);
+    }
+    // Config::validate rejects unreadable CA bundles
+    if let Some(path) = &config.performance.ca_bundle
+        && let Ok(certs) = ca_certificates(path)
+    {
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if config.performance.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    // Config::validate rejects invalid or reserved header names; default headers
+    // never replace headers set on the request itself
+    if let Ok(headers) = custom_headers(config) {
+        builder = builder.default_headers(headers);
+    }
+    builder.build().expect("Failed to create HTTP client")
+}
+
+/// Load every certificate from a PEM bundle
+pub fn ca_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let certs = Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("Invalid PEM in CA bundle {}", path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in CA bundle {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// `[http.headers]` from the config as a header map, marked sensitive so they are
+/// redacted from trace output
+pub fn custom_headers(config: &Config) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.http.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid header name '{}' in [http.headers]", name))?;
+        if header_name == AUTHORIZATION || header_name == CONTENT_TYPE {
+            anyhow::bail!(
+                "Header '{}' in [http.headers] is set by atlassian-cli and cannot be overridden",
+                name
+            );
+        }
+        let mut header_value = HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for header '{}' in [http.headers]", name))?;
+        header_value.set_sensitive(true);
+        headers.insert(header_name, header_value);
+    }
+    Ok(headers)
+}
+
+fn proxy(config: &Config) -> Option<Proxy> {
+    let url = config.performance.proxy.as_deref()?;
+    // Config::validate rejects unparsable proxy URLs
+    let proxy = Proxy::all(url).ok()?;
+    let no_proxy = match config.performance.no_proxy.as_deref() {
+        Some(hosts) => NoProxy::from_string(hosts),
+        None => NoProxy::from_env(),
+    };
+    Some(proxy.no_proxy(no_proxy))
 }
 
 pub fn auth_header(config: &Config) -> String {
     use base64::{Engine as _, engine::general_purpose::STANDARD};
-    let credentials = format!("{}:{}", config.email(), config.token());
-    format!("Basic {}", STANDARD.encode(credentials))
+    match config.auth_type {
+        AuthType::Bearer => format!("Bearer {}", config.token()),
+        AuthType::Basic => {
+            let credentials = format!("{}:{}", config.email(), config.token());
+            format!("Basic {}", STANDARD.encode(credentials))
+        }
+    }
+}
+
+/// Add `X-Atlassian-Token: no-check` to a write request when
+/// `[http] atlassian_token_no_check` is on
+pub fn no_check(builder: RequestBuilder, config: &Config) -> RequestBuilder {
+    if config.http.atlassian_token_no_check {
+        builder.header("X-Atlassian-Token", "no-check")
+    } else {
+        builder
+    }
+}
+
+/// Send a request once the shared rate limiter allows it, logging method, path, status
+/// and elapsed time at debug level (`-vv`) and the response body size at trace level (`-vvv`).
+pub async fn send_timed(builder: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    // Sensitive values are printed as `Sensitive` by reqwest/hyper trace output
+    if let Some(value) = request.headers_mut().get_mut(AUTHORIZATION) {
+        value.set_sensitive(true);
+    }
+    let method = request.method().clone();
+    let path = request.url().path().to_string();
+
+    let dump = DUMP_DIR.get().map(|dir| (dir, describe_request(&request)));
+
+    acquire_rate_limit().await;
+    let start = Instant::now();
+    let mut result = client.execute(request).await;
+    let elapsed_ms = start.elapsed().as_millis();
+
+    if let Some((dir, request_dump)) = dump {
+        result = match result {
+            Ok(response) => {
+                dump_exchange(dir, &method, &path, request_dump, response, elapsed_ms).await
+            }
+            Err(e) => {
+                let text = format!("{}\n=== ERROR ===\n{}\n", request_dump, e);
+                write_dump(dir, &method, &path, &text);
+                Err(e)
+            }
+        };
+    }
+
+    match &result {
+        Ok(response) => {
+            tracing::debug!(
+                %method,
+                path,
+                status = response.status().as_u16(),
+                elapsed_ms,
+                "HTTP request"
+            );
+            tracing::trace!(
+                %method,
+                path,
+                body_bytes = response.content_length(),
+                "HTTP response body"
+            );
+        }
+        Err(e) => {
+            tracing::debug!(%method, path, elapsed_ms, error = %e, "HTTP request failed");
+        }
+    }
+
+    result
+}
+
+/// Write every request/response pair to timestamped files in `dir` (`--dump`).
+/// Credentials and other sensitive headers are redacted.
+pub fn enable_dump(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create dump directory {}", dir.display()))?;
+    let _ = DUMP_DIR.set(dir.to_path_buf());
+    Ok(())
+}
+
+fn describe_request(request: &reqwest::Request) -> String {
+    let mut text = format!(
+        "=== REQUEST ===\n{} {}\n{}",
+        request.method(),
+        request.url(),
+        describe_headers(request.headers())
+    );
+    if let Some(body) = request.body() {
+        match body.as_bytes() {
+            Some(bytes) => {
+                text.push('\n');
+                text.push_str(&describe_body(bytes));
+                text.push('\n');
+            }
+            None => text.push_str("\n(streamed body not captured)\n"),
+        }
+    }
+    text
+}
+
+fn describe_headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let redacted = value.is_sensitive()
+                || name == AUTHORIZATION
+                || name == reqwest::header::COOKIE
+                || name == reqwest::header::SET_COOKIE;
+            let value = if redacted {
+                "***".into()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            format!("{}: {}\n", name, value)
+        })
+        .collect()
+}
+
+/// Read a textual response body for the dump, then hand back an equivalent response.
+/// Binary bodies (attachment downloads) are passed through without buffering.
+async fn dump_exchange(
+    dir: &Path,
+    method: &reqwest::Method,
+    path: &str,
+    request_dump: String,
+    response: Response,
+    elapsed_ms: u128,
+) -> reqwest::Result<Response> {
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let head = format!(
+        "{}\n=== RESPONSE ===\n{} ({} ms)\n{}",
+        request_dump,
+        status,
+        elapsed_ms,
+        describe_headers(&headers)
+    );
+
+    if !is_textual(&headers) {
+        let text = format!("{}\n(binary body not captured)\n", head);
+        write_dump(dir, method, path, &text);
+        return Ok(response);
+    }
+
+    let body = response.bytes().await?;
+    let text = format!("{}\n{}\n", head, describe_body(&body));
+    write_dump(dir, method, path, &text);
+
+    let mut rebuilt = ::http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(Response::from(rebuilt))
+}
+
+/// JSON, XML and `text/*` bodies are worth capturing; a missing type is assumed textual
+fn is_textual(headers: &HeaderMap) -> bool {
+    let Some(value) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    let mime = value
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("json")
+        || mime.ends_with("xml")
+        || mime == "application/x-www-form-urlencoded"
+}
+
+fn describe_body(bytes: &[u8]) -> String {
+    if bytes.len() <= DUMP_BODY_LIMIT {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    format!(
+        "{}\n... ({} more bytes truncated)",
+        String::from_utf8_lossy(&bytes[..DUMP_BODY_LIMIT]),
+        bytes.len() - DUMP_BODY_LIMIT
+    )
+}
+
+/// Dumping is best effort: a failed write is logged and the command carries on
+fn write_dump(dir: &Path, method: &reqwest::Method, path: &str, text: &str) {
+    let seq = DUMP_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+    let file = dir.join(dump_file_name(
+        &chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string(),
+        seq,
+        method.as_str(),
+        path,
+    ));
+    if let Err(e) = std::fs::write(&file, text) {
+        tracing::warn!(file = %file.display(), error = %e, "Failed to write request dump");
+    }
+}
+
+/// e.g. `20240101T120000.000Z-0003-GET-rest_api_3_issue_PROJ-1.txt`
+fn dump_file_name(timestamp: &str, seq: usize, method: &str, path: &str) -> String {
+    let slug: String = path
+        .trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(80)
+        .collect();
+    format!("{}-{:04}-{}-{}.txt", timestamp, seq, method, slug)
+}
+
+/// Extract a readable message from an Atlassian error response body.
+/// Understands Jira (`errorMessages`, `errors`) and Confluence (`message`, `data.errors`)
+/// shapes and falls back to the raw body.
+pub fn error_message(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.to_string();
+    };
+
+    let mut messages: Vec<String> = Vec::new();
+
+    if let Some(errors) = value["errorMessages"].as_array() {
+        messages.extend(errors.iter().filter_map(|e| e.as_str()).map(String::from));
+    }
+    if let Some(errors) = value["errors"].as_object() {
+        messages.extend(
+            errors
+                .iter()
+                .filter_map(|(field, msg)| msg.as_str().map(|m| format!("{}: {}", field, m))),
+        );
+    }
+    if let Some(errors) = value["data"]["errors"].as_array() {
+        messages.extend(errors.iter().filter_map(|e| {
+            e["message"]["translation"]
+                .as_str()
+                .or_else(|| e["message"]["key"].as_str())
+                .map(String::from)
+        }));
+    }
+    if messages.is_empty()
+        && let Some(message) = value["message"].as_str()
+    {
+        messages.push(message.to_string());
+    }
+
+    if messages.is_empty() {
+        body.to_string()
+    } else {
+        messages.join("; ")
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::create_test_config;
+    use crate::test_utils::{create_test_config, serve, serve_once, use_stub};
+
+    #[test]
+    fn test_token_bucket_allows_burst_then_waits() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, start);
+        assert!(bucket.try_acquire(start).is_none());
+        assert!(bucket.try_acquire(start).is_none());
+
+        let wait = bucket.try_acquire(start).unwrap();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        assert!(bucket.try_acquire(start + wait).is_none());
+        assert!(bucket.try_acquire(start + wait).is_some());
+    }
+
+    #[test]
+    fn test_token_bucket_refill_is_capped() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1.0, start);
+        assert!(bucket.try_acquire(start).is_none());
+
+        let later = start + Duration::from_secs(60);
+        assert!(bucket.try_acquire(later).is_none());
+        assert!(bucket.try_acquire(later).is_some());
+    }
+
+    #[test]
+    fn test_token_bucket_disabled() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(0.0, start);
+        for _ in 0..100 {
+            assert!(bucket.try_acquire(start).is_none());
+        }
+    }
 
     #[test]
     fn test_client_creation() {
@@ -27,6 +472,28 @@ mod tests {
         assert!(format!("{:?}", client).contains("Client"));
     }
 
+    #[test]
+    fn test_client_with_proxy() {
+        let mut config = create_test_config();
+        assert!(proxy(&config).is_none());
+
+        config.performance.proxy = Some("http://proxy.example.com:8080".to_string());
+        config.performance.no_proxy = Some("localhost".to_string());
+        let proxy = proxy(&config).expect("proxy should be configured");
+        assert!(format!("{:?}", proxy).contains("proxy.example.com"));
+
+        let client = client(&config);
+        assert!(format!("{:?}", client).contains("http://proxy.example.com:8080"));
+    }
+
+    #[test]
+    fn test_ca_certificates_rejects_non_pem() {
+        let path = std::env::temp_dir().join("atlassian-cli-test-not-a-cert.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+        assert!(ca_certificates(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_auth_header_format() {
         let config = create_test_config();
@@ -45,4 +512,180 @@ mod tests {
         let credentials = String::from_utf8(decoded).unwrap();
         assert_eq!(credentials, "test@example.com:token123");
     }
+
+    #[test]
+    fn test_auth_header_bearer() {
+        let mut config = create_test_config();
+        config.auth_type = AuthType::Bearer;
+        config.email = None;
+        assert_eq!(auth_header(&config), "Bearer token123");
+    }
+
+    #[test]
+    fn test_error_message_jira_shape() {
+        let body =
+            r#"{"errorMessages":["Issue does not exist"],"errors":{"summary":"Field required"}}"#;
+        assert_eq!(
+            error_message(body),
+            "Issue does not exist; summary: Field required"
+        );
+    }
+
+    #[test]
+    fn test_error_message_confluence_shape() {
+        let body = r#"{"statusCode":400,"message":"com.atlassian.confluence.api.service.exceptions.BadRequestException: Could not parse cql : type = ","data":{"errors":[]}}"#;
+        assert!(error_message(body).starts_with("com.atlassian"));
+    }
+
+    #[test]
+    fn test_error_message_non_json() {
+        assert_eq!(error_message("Bad Gateway"), "Bad Gateway");
+    }
+
+    #[test]
+    fn test_custom_headers_rejects_reserved_and_invalid() {
+        let mut config = create_test_config();
+        config
+            .http
+            .headers
+            .insert("authorization".into(), "Basic x".into());
+        assert!(custom_headers(&config).is_err());
+
+        config.http.headers.clear();
+        config.http.headers.insert("Bad Name".into(), "x".into());
+        assert!(custom_headers(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_custom_header_sent_with_request() {
+        let (base_url, server) = serve_once("200 OK", "");
+
+        let mut config = create_test_config();
+        use_stub(&mut config, &base_url);
+        config
+            .http
+            .headers
+            .insert("X-Forwarded-User".into(), "alice".into());
+
+        let response = send_timed(
+            client(&config)
+                .get(format!("{}/rest/api/3/myself", base_url))
+                .header("Authorization", auth_header(&config)),
+        )
+        .await
+        .unwrap();
+        assert!(response.status().is_success());
+
+        let request = server.join().unwrap();
+        assert!(
+            request
+                .headers
+                .contains(&"x-forwarded-user: alice".to_string())
+        );
+        assert!(
+            request
+                .headers
+                .iter()
+                .any(|h| h.starts_with("authorization: basic "))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_reused_across_requests() {
+        let (base_url, server) = serve(vec![("200 OK", ""), ("200 OK", "")]);
+
+        let mut config = create_test_config();
+        use_stub(&mut config, &base_url);
+        for path in [
+            "/rest/api/3/issue/PROJ-1",
+            "/rest/api/3/issue/PROJ-1/comment",
+        ] {
+            let response = send_timed(client(&config).get(format!("{}{}", base_url, path)))
+                .await
+                .unwrap();
+            assert!(response.status().is_success());
+        }
+
+        assert_eq!(
+            server.join().unwrap().connections,
+            1,
+            "second request opened a new connection"
+        );
+    }
+
+    #[test]
+    fn test_no_check_follows_config() {
+        let mut config = create_test_config();
+        let build = |config: &Config| {
+            no_check(
+                Client::new().post("https://test.atlassian.net/rest/api/3/issue"),
+                config,
+            )
+            .build()
+            .unwrap()
+        };
+        assert!(build(&config).headers().get("X-Atlassian-Token").is_none());
+
+        config.http.atlassian_token_no_check = true;
+        assert_eq!(build(&config).headers()["X-Atlassian-Token"], "no-check");
+    }
+
+    #[test]
+    fn test_dump_file_name() {
+        assert_eq!(
+            dump_file_name("20240101T120000.000Z", 3, "GET", "/rest/api/3/issue/PROJ-1"),
+            "20240101T120000.000Z-0003-GET-rest_api_3_issue_PROJ-1.txt"
+        );
+    }
+
+    #[test]
+    fn test_is_textual() {
+        let with_type = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(value));
+            headers
+        };
+        assert!(is_textual(&HeaderMap::new()));
+        assert!(is_textual(&with_type("application/json;charset=UTF-8")));
+        assert!(is_textual(&with_type("text/html")));
+        assert!(is_textual(&with_type("application/vnd.api+json")));
+        assert!(!is_textual(&with_type("application/octet-stream")));
+        assert!(!is_textual(&with_type("image/png")));
+        assert!(!is_textual(&with_type("application/pdf")));
+    }
+
+    #[test]
+    fn test_describe_body_caps_length() {
+        assert_eq!(describe_body(b"{\"ok\":true}"), "{\"ok\":true}");
+
+        let long = vec![b'a'; DUMP_BODY_LIMIT + 10];
+        let text = describe_body(&long);
+        assert!(text.starts_with(&"a".repeat(DUMP_BODY_LIMIT)));
+        assert!(text.ends_with("... (10 more bytes truncated)"));
+    }
+
+    #[test]
+    fn test_describe_request_redacts_credentials() {
+        let config = create_test_config();
+        let mut custom = HeaderValue::from_static("csrf-secret");
+        custom.set_sensitive(true);
+        let request = Client::new()
+            .post("https://test.atlassian.net/rest/api/3/issue")
+            .header("Authorization", auth_header(&config))
+            .header("X-Csrf-Token", custom)
+            .header("Content-Type", "application/json")
+            .body(r#"{"fields":{}}"#)
+            .build()
+            .unwrap();
+
+        let text = describe_request(&request);
+        assert!(
+            text.starts_with("=== REQUEST ===\nPOST https://test.atlassian.net/rest/api/3/issue\n")
+        );
+        assert!(text.contains("authorization: ***\n"));
+        assert!(text.contains("x-csrf-token: ***\n"));
+        assert!(text.contains("content-type: application/json\n"));
+        assert!(text.contains(r#"{"fields":{}}"#));
+        assert!(!text.contains(&auth_header(&config)));
+    }
 }
@@ -192,15 +192,11 @@ fn markdown_to_adf(text: &str) -> Value {
                     marks.push(json!({"type": "strike"}));
                 }
                 Tag::Link { dest_url, .. } => {
-                    marks.push(
-                        json!({"type": "link", "attrs": {"href": dest_url.as_ref()}}),
-                    );
+                    marks.push(json!({"type": "link", "attrs": {"href": dest_url.as_ref()}}));
                 }
                 Tag::Image { dest_url, .. } => {
                     // ADF doesn't have a direct inline image in text. Represent as link.
-                    marks.push(
-                        json!({"type": "link", "attrs": {"href": dest_url.as_ref()}}),
-                    );
+                    marks.push(json!({"type": "link", "attrs": {"href": dest_url.as_ref()}}));
                 }
                 _ => {}
             },
@@ -223,8 +219,7 @@ fn markdown_to_adf(text: &str) -> Value {
                         // listItem and tableCell in ADF require block-level children.
                         // Tight lists in pulldown-cmark produce inline content directly
                         // under Item without a Paragraph wrapper — we add one.
-                        let needs_block_wrap =
-                            matches!(tag_end, TagEnd::Item | TagEnd::TableCell);
+                        let needs_block_wrap = matches!(tag_end, TagEnd::Item | TagEnd::TableCell);
                         let final_children = if needs_block_wrap && !children.is_empty() {
                             wrap_inline_in_paragraphs(children)
                         } else {
@@ -251,17 +246,19 @@ fn markdown_to_adf(text: &str) -> Value {
                         TagEnd::Strikethrough => "strike",
                         _ => unreachable!(),
                     };
-                    if let Some(pos) = marks.iter().rposition(|m| {
-                        m.get("type").and_then(|t| t.as_str()) == Some(mark_type)
-                    }) {
+                    if let Some(pos) = marks
+                        .iter()
+                        .rposition(|m| m.get("type").and_then(|t| t.as_str()) == Some(mark_type))
+                    {
                         marks.remove(pos);
                     }
                 }
                 TagEnd::Link | TagEnd::Image => {
                     // Pop the link mark
-                    if let Some(pos) = marks.iter().rposition(|m| {
-                        m.get("type").and_then(|t| t.as_str()) == Some("link")
-                    }) {
+                    if let Some(pos) = marks
+                        .iter()
+                        .rposition(|m| m.get("type").and_then(|t| t.as_str()) == Some("link"))
+                    {
                         marks.remove(pos);
                     }
                 }
@@ -414,6 +411,23 @@ pub fn process_adf_input(value: Value, field_name: &str) -> Result<Value> {
     }
 }
 
+/// Processes rich-text input for Jira REST API v2 (Data Center), which takes
+/// plain text / wiki markup strings instead of ADF.
+///
+/// # Errors
+/// Returns error for ADF objects and non-string values
+pub fn process_wiki_input(value: Value, field_name: &str) -> Result<Value> {
+    match value {
+        Value::String(_) => Ok(value),
+        Value::Null => Ok(Value::String(String::new())),
+        Value::Object(_) => anyhow::bail!(
+            "{} as ADF is not supported on Data Center (REST API v2); pass plain text or wiki markup",
+            field_name
+        ),
+        _ => anyhow::bail!("{} must be a string, got {:?}", field_name, value),
+    }
+}
+
 /// Processes description input for create/update issue operations.
 ///
 /// Convenience wrapper around process_adf_input with field name "description".
@@ -442,6 +456,20 @@ pub fn process_comment_input(value: Value) -> Result<Value> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_process_wiki_input() {
+        assert_eq!(
+            process_wiki_input(json!("h1. Title"), "description").unwrap(),
+            json!("h1. Title")
+        );
+        assert_eq!(
+            process_wiki_input(Value::Null, "description").unwrap(),
+            json!("")
+        );
+        let err = process_wiki_input(json!({"type": "doc"}), "comment").unwrap_err();
+        assert!(err.to_string().contains("Data Center"));
+    }
+
     // Tests for validate_adf function
 
     #[test]
@@ -1338,10 +1366,12 @@ mod tests {
         let block = &adf["content"][0];
         assert_eq!(block["type"], "codeBlock");
         assert_eq!(block["attrs"]["language"], "rust");
-        assert!(block["content"][0]["text"]
-            .as_str()
-            .unwrap()
-            .contains("fn main()"));
+        assert!(
+            block["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("fn main()")
+        );
     }
 
     #[test]
@@ -1449,11 +1479,13 @@ mod tests {
         assert_eq!(italic_node["marks"][0]["type"], "em");
         // "code" - code mark
         let code_node = nodes.iter().find(|n| n["text"] == "code").unwrap();
-        assert!(code_node["marks"]
-            .as_array()
-            .unwrap()
-            .iter()
-            .any(|m| m["type"] == "code"));
+        assert!(
+            code_node["marks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|m| m["type"] == "code")
+        );
     }
 
     #[test]
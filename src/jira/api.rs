@@ -1,53 +1,75 @@
+use crate::attachment;
 use crate::config::Config;
 use crate::filter;
 use crate::http;
 use crate::jira::adf;
 use crate::jira::fields;
-use crate::markdown::adf_to_markdown;
+use crate::markdown::{AdfOptions, adf_to_markdown_with};
+use crate::pagination;
+use crate::util;
 use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
 use serde_json::{Value, json};
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
 use std::path::Path;
 use std::time::Duration;
-use tokio::time::sleep;
 
-fn convert_issue_to_markdown(issue: &mut Value) {
+fn adf_to_markdown(adf: &Value, config: &Config) -> String {
+    adf_to_markdown_with(adf, &AdfOptions::from_config(config))
+}
+
+fn convert_issue_to_markdown(issue: &mut Value, config: &Config) {
     let Some(fields) = issue.get_mut("fields") else {
         return;
     };
     let Some(desc) = fields.get_mut("description") else {
         return;
     };
-    if desc.is_object() {
-        *desc = Value::String(adf_to_markdown(desc));
+    *desc = description_to_markdown(desc, config);
+}
+
+/// ADF descriptions become markdown; anything else that isn't a string or null (a number
+/// or array from an older API or a misconfigured custom field) is kept in its JSON text form
+fn description_to_markdown(desc: &Value, config: &Config) -> Value {
+    match desc {
+        Value::Object(_) => Value::String(adf_to_markdown(desc, config)),
+        Value::Null | Value::String(_) => desc.clone(),
+        other => Value::String(other.to_string()),
     }
 }
 
-fn convert_issues_to_markdown(result: &mut Value) {
+fn convert_issues_to_markdown(result: &mut Value, config: &Config) {
     let Some(items) = result.get_mut("items").and_then(|i| i.as_array_mut()) else {
         return;
     };
 
     for issue in items {
-        convert_issue_to_markdown(issue);
+        convert_issue_to_markdown(issue, config);
     }
 }
 
 const MAX_RESULTS_PER_PAGE: u32 = 100;
 
-fn apply_project_filter(jql: &str, config: &Config) -> String {
-    if config.jira.projects_filter.is_empty() {
-        return jql.to_string();
-    }
-
+/// Split JQL into its conditions and an optional ` ORDER BY ...` clause (leading space kept)
+fn split_order_by(jql: &str) -> (String, Option<String>) {
     let jql_lower = jql.to_lowercase();
-    let (conditions, order_by) = if let Some(pos) = jql_lower.find(" order by ") {
+    if let Some(pos) = jql_lower.find(" order by ") {
         (jql[..pos].to_string(), Some(jql[pos..].to_string()))
     } else if jql_lower.starts_with("order by ") {
         (String::new(), Some(format!(" {}", jql)))
     } else {
         (jql.to_string(), None)
-    };
+    }
+}
+
+fn apply_project_filter(jql: &str, config: &Config) -> String {
+    if config.jira.projects_filter.is_empty() {
+        return jql.to_string();
+    }
+
+    let (conditions, order_by) = split_order_by(jql);
 
     let conditions_lower = conditions.to_lowercase();
     if conditions_lower.contains("project ")
@@ -78,11 +100,186 @@ fn apply_project_filter(jql: &str, config: &Config) -> String {
     }
 }
 
+/// Reject queries that would come back as an opaque 400. Run on the final JQL, after
+/// project filter injection, so an empty query with `projects_filter` set is still valid.
+fn validate_jql(jql: &str) -> Result<()> {
+    let (conditions, _) = split_order_by(jql.trim());
+    if conditions.trim().is_empty() {
+        anyhow::bail!("JQL query is empty (pass a query or set jira.projects_filter)");
+    }
+    util::check_query_syntax(jql, "JQL")
+}
+
+/// Parse a relative duration like `7d`, `24h`, `2w` or `30m` (minutes) into a JQL offset (`-7d`)
+fn parse_jql_duration(duration: &str) -> Result<String> {
+    let duration = duration.trim();
+    let unit_start = duration.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = duration.split_at(unit_start);
+    if amount.is_empty()
+        || !amount.chars().all(|c| c.is_ascii_digit())
+        || !matches!(unit, "d" | "h" | "w" | "m")
+    {
+        anyhow::bail!(
+            "Invalid duration '{}': expected a number followed by d, h, w or m (e.g. 7d)",
+            duration
+        );
+    }
+    Ok(format!("-{}{}", amount, unit))
+}
+
+/// AND `created >= -Nd` / `updated >= -Nd` predicates into the JQL, keeping ORDER BY last
+pub fn apply_since_filters(
+    jql: &str,
+    created_since: Option<&str>,
+    updated_since: Option<&str>,
+) -> Result<String> {
+    let mut predicates: Vec<String> = Vec::new();
+    if let Some(since) = created_since {
+        predicates.push(format!("created >= {}", parse_jql_duration(since)?));
+    }
+    if let Some(since) = updated_since {
+        predicates.push(format!("updated >= {}", parse_jql_duration(since)?));
+    }
+    if predicates.is_empty() {
+        return Ok(jql.to_string());
+    }
+
+    let (conditions, order_by) = split_order_by(jql.trim());
+    let predicates = predicates.join(" AND ");
+    let base = if conditions.trim().is_empty() {
+        predicates
+    } else {
+        format!("({}) AND {}", conditions.trim(), predicates)
+    };
+
+    Ok(format!("{}{}", base, order_by.unwrap_or_default()))
+}
+
+/// Replace the JQL's ORDER BY clause with `--order` specs (`field` or `field:asc|desc`).
+/// Without specs the JQL is returned unchanged.
+pub fn apply_order(jql: &str, orders: &[String]) -> Result<String> {
+    if orders.is_empty() {
+        return Ok(jql.to_string());
+    }
+
+    let clauses = orders
+        .iter()
+        .map(|spec| {
+            let (field, direction) = spec.rsplit_once(':').unwrap_or((spec.as_str(), "asc"));
+            let field = field.trim();
+            if field.is_empty() || field.contains(char::is_whitespace) {
+                anyhow::bail!(
+                    "Invalid --order '{}': expected field:asc or field:desc",
+                    spec
+                );
+            }
+            let direction = match direction.trim().to_lowercase().as_str() {
+                "asc" => "ASC",
+                "desc" => "DESC",
+                _ => anyhow::bail!("Invalid --order '{}': direction must be asc or desc", spec),
+            };
+            Ok(format!("{} {}", field, direction))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (conditions, _) = split_order_by(jql.trim());
+    let order_by = format!("ORDER BY {}", clauses.join(", "));
+    Ok(if conditions.trim().is_empty() {
+        order_by
+    } else {
+        format!("{} {}", conditions.trim(), order_by)
+    })
+}
+
+/// Field used by `jira search --group-by` to aggregate issue counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Status,
+    Assignee,
+    Type,
+    Priority,
+}
+
+impl GroupBy {
+    /// Jira field that must be requested for grouping
+    pub fn api_field(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Assignee => "assignee",
+            Self::Type => "issuetype",
+            Self::Priority => "priority",
+        }
+    }
+
+    fn label(self, issue: &Value) -> String {
+        let field = &issue["fields"][self.api_field()];
+        let name = match self {
+            Self::Assignee => field["displayName"].as_str(),
+            _ => field["name"].as_str(),
+        };
+        let fallback = match self {
+            Self::Assignee => "Unassigned",
+            _ => "None",
+        };
+        name.unwrap_or(fallback).to_string()
+    }
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "status" => Ok(Self::Status),
+            "assignee" => Ok(Self::Assignee),
+            "type" | "issuetype" => Ok(Self::Type),
+            "priority" => Ok(Self::Priority),
+            other => anyhow::bail!(
+                "Unsupported group-by field '{}': expected status, assignee, type or priority",
+                other
+            ),
+        }
+    }
+}
+
+/// Replace search `items` with `{"groups": {label: count}, "total": N}`, largest groups first
+pub fn group_issues(result: &Value, group_by: GroupBy) -> Value {
+    let issues = result["items"].as_array().cloned().unwrap_or_default();
+    let mut counts: Vec<(String, u64)> = Vec::new();
+    for issue in &issues {
+        let label = group_by.label(issue);
+        match counts.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((label, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let groups: serde_json::Map<String, Value> = counts
+        .into_iter()
+        .map(|(label, count)| (label, json!(count)))
+        .collect();
+    json!({"groups": groups, "total": issues.len()})
+}
+
 fn extract_display_name(value: &Value) -> Value {
-    value
-        .get("displayName")
-        .cloned()
-        .unwrap_or(Value::Null)
+    value.get("displayName").cloned().unwrap_or(Value::Null)
+}
+
+/// `{accountId, displayName}` for a user field; Data Center users also keep their `name`,
+/// which is what assignment takes there
+fn extract_user_ref(value: &Value) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+    let mut user = json!({
+        "accountId": value.get("accountId").cloned().unwrap_or(Value::Null),
+        "displayName": extract_display_name(value),
+    });
+    if let Some(name) = value.get("name") {
+        user["name"] = name.clone();
+    }
+    user
 }
 
 /// Inject attachment URLs into [Media: filename] references as markdown image links
@@ -100,10 +297,9 @@ fn inject_media_links(text: &str, attachments: &[Value]) -> String {
             if let (Some(att_filename), Some(att_content)) = (
                 attachment.get("filename").and_then(|f| f.as_str()),
                 attachment.get("content").and_then(|c| c.as_str()),
-            ) {
-                if att_filename == filename {
-                    return format!("![{}]({})", filename, att_content);
-                }
+            ) && att_filename == filename
+            {
+                return format!("![{}]({})", filename, att_content);
             }
         }
 
@@ -113,19 +309,18 @@ fn inject_media_links(text: &str, attachments: &[Value]) -> String {
     .to_string()
 }
 
-fn simplify_issue(data: &Value, as_markdown: bool) -> Value {
+fn simplify_issue(data: &Value, as_markdown: bool, full_users: bool, config: &Config) -> Value {
     let fields = &data["fields"];
+    let user = if full_users {
+        extract_user_ref
+    } else {
+        extract_display_name
+    };
 
     let description = if as_markdown {
         fields
             .get("description")
-            .map(|d| {
-                if d.is_object() {
-                    Value::String(adf_to_markdown(d))
-                } else {
-                    d.clone()
-                }
-            })
+            .map(|d| description_to_markdown(d, config))
             .unwrap_or(Value::Null)
     } else {
         fields.get("description").cloned().unwrap_or(Value::Null)
@@ -137,8 +332,8 @@ fn simplify_issue(data: &Value, as_markdown: bool) -> Value {
         "type": fields.get("issuetype").and_then(|t| t.get("name")).cloned().unwrap_or(Value::Null),
         "status": fields.get("status").and_then(|s| s.get("name")).cloned().unwrap_or(Value::Null),
         "priority": fields.get("priority").and_then(|p| p.get("name")).cloned().unwrap_or(Value::Null),
-        "assignee": fields.get("assignee").map(extract_display_name).unwrap_or(Value::Null),
-        "reporter": fields.get("reporter").map(extract_display_name).unwrap_or(Value::Null),
+        "assignee": fields.get("assignee").map(user).unwrap_or(Value::Null),
+        "reporter": fields.get("reporter").map(user).unwrap_or(Value::Null),
         "project": fields.get("project").and_then(|p| p.get("name")).cloned().unwrap_or(Value::Null),
         "created": fields.get("created").cloned().unwrap_or(Value::Null),
         "updated": fields.get("updated").cloned().unwrap_or(Value::Null),
@@ -156,12 +351,30 @@ fn simplify_attachment(attachment: &Value) -> Value {
     })
 }
 
+fn simplify_user(user: &Value) -> Value {
+    json!({
+        "accountId": user.get("accountId").cloned().unwrap_or(Value::Null),
+        "displayName": user.get("displayName").cloned().unwrap_or(Value::Null),
+        "emailAddress": user.get("emailAddress").cloned().unwrap_or(Value::Null),
+        "timeZone": user.get("timeZone").cloned().unwrap_or(Value::Null),
+        "active": user.get("active").cloned().unwrap_or(Value::Null),
+    })
+}
+
 fn simplify_issuelink(link: &Value) -> Value {
-    let link_type = link.get("type").and_then(|t| t.get("name")).cloned().unwrap_or(Value::Null);
+    let link_type = link
+        .get("type")
+        .and_then(|t| t.get("name"))
+        .cloned()
+        .unwrap_or(Value::Null);
 
     // Check if it's an inward or outward link
     if let Some(inward) = link.get("inwardIssue") {
-        let direction = link.get("type").and_then(|t| t.get("inward")).cloned().unwrap_or(Value::Null);
+        let direction = link
+            .get("type")
+            .and_then(|t| t.get("inward"))
+            .cloned()
+            .unwrap_or(Value::Null);
         json!({
             "type": link_type,
             "direction": direction,
@@ -170,7 +383,11 @@ fn simplify_issuelink(link: &Value) -> Value {
             "status": inward.get("fields").and_then(|f| f.get("status")).and_then(|s| s.get("name")).cloned().unwrap_or(Value::Null),
         })
     } else if let Some(outward) = link.get("outwardIssue") {
-        let direction = link.get("type").and_then(|t| t.get("outward")).cloned().unwrap_or(Value::Null);
+        let direction = link
+            .get("type")
+            .and_then(|t| t.get("outward"))
+            .cloned()
+            .unwrap_or(Value::Null);
         json!({
             "type": link_type,
             "direction": direction,
@@ -183,21 +400,105 @@ fn simplify_issuelink(link: &Value) -> Value {
     }
 }
 
-pub async fn get_issue(issue_key: &str, as_markdown: bool, config: &Config) -> Result<Value> {
+/// What `jira get` fetches besides the issue itself
+#[derive(Debug, Clone, Default)]
+pub struct GetIssueOptions {
+    pub include_comments: bool,
+    pub include_attachments: bool,
+    pub fields: Option<Vec<String>>,
+    /// Passed through as the `expand` query param (e.g. `changelog`, `renderedFields`)
+    pub expand: Option<Vec<String>>,
+    pub full_users: bool,
+}
+
+pub async fn get_issue(
+    issue_key: &str,
+    as_markdown: bool,
+    options: GetIssueOptions,
+    config: &Config,
+) -> Result<Value> {
+    let GetIssueOptions {
+        include_comments,
+        include_attachments,
+        fields: api_fields,
+        expand,
+        full_users,
+    } = options;
+    let expand = expand.as_deref();
+
+    // Escape hatch: the raw issue with every field, bypassing the curated view
+    if fields::is_all_fields(api_fields.as_deref()) {
+        let mut data = fetch_issue_data(issue_key, &["*all".to_string()], expand, config).await?;
+        filter::apply(&mut data, config);
+        return Ok(data);
+    }
+
+    let explicit = api_fields
+        .as_ref()
+        .is_some_and(|f| f.iter().any(|f| !f.starts_with('-')));
+    let (mut requested_fields, include_comments) =
+        fields::resolve_get_fields(api_fields, include_attachments, include_comments);
+
+    // `--raw`: the selected fields as Jira returns them, comments included in `fields.comment`
+    if config.raw {
+        if include_comments {
+            requested_fields.push("comment".to_string());
+        }
+        let mut data = fetch_issue_data(issue_key, &requested_fields, expand, config).await?;
+        if as_markdown {
+            convert_issue_to_markdown(&mut data, config);
+        }
+        return Ok(data);
+    }
+
+    // Comments only depend on the issue key, so fetch them alongside the issue
+    let comments = async {
+        if include_comments {
+            Some(fetch_comments_for_issue(issue_key, as_markdown, config).await)
+        } else {
+            None
+        }
+    };
+    let (data, comments) = tokio::join!(
+        fetch_issue_data(issue_key, &requested_fields, expand, config),
+        comments
+    );
+    let data = data?;
+
+    let mut issue = assemble_issue(&data, comments, as_markdown, full_users, config);
+    if explicit {
+        retain_requested_fields(&mut issue, &data, &requested_fields);
+    }
+    if expand.is_some() {
+        append_expanded(&mut issue, &data);
+    }
+    Ok(issue)
+}
+
+async fn fetch_issue_data(
+    issue_key: &str,
+    requested_fields: &[String],
+    expand: Option<&[String]>,
+    config: &Config,
+) -> Result<Value> {
     let client = http::client(config);
-    // Include attachment and issuelinks fields
-    let url = format!(
-        "{}/rest/api/3/issue/{}?fields=summary,description,status,priority,issuetype,assignee,reporter,project,created,updated,attachment,issuelinks",
-        config.base_url(),
-        issue_key
+    let mut url = format!(
+        "{}/issue/{}?fields={}",
+        config.jira_api(),
+        issue_key,
+        requested_fields.join(",")
     );
+    if let Some(expand) = expand.filter(|e| !e.is_empty()) {
+        url.push_str(&format!("&expand={}", expand.join(",")));
+    }
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -205,8 +506,31 @@ pub async fn get_issue(issue_key: &str, as_markdown: bool, config: &Config) -> R
         anyhow::bail!("Failed to get issue ({}): {}", status, body);
     }
 
-    let data: Value = response.json().await?;
+    Ok(response.json().await?)
+}
+
+/// Copy sections added by `expand` (e.g. `changelog`, `renderedFields`, `transitions`)
+/// from the raw response into the curated view, unchanged
+fn append_expanded(issue: &mut Value, data: &Value) {
+    let (Some(obj), Some(raw)) = (issue.as_object_mut(), data.as_object()) else {
+        return;
+    };
+    for (key, value) in raw {
+        if !matches!(key.as_str(), "expand" | "id" | "self" | "key" | "fields") {
+            obj.insert(key.clone(), value.clone());
+        }
+    }
+}
 
+/// Merge the issue response and its (already fetched) comments into the `get` output.
+/// Media links are injected only after both are available.
+fn assemble_issue(
+    data: &Value,
+    comments: Option<Vec<Value>>,
+    as_markdown: bool,
+    full_users: bool,
+    config: &Config,
+) -> Value {
     // Extract attachments first (needed for media link injection)
     let attachments: Vec<Value> = data["fields"]["attachment"]
         .as_array()
@@ -217,23 +541,22 @@ pub async fn get_issue(issue_key: &str, as_markdown: bool, config: &Config) -> R
         .collect();
 
     // Simplify issue structure
-    let mut simplified = simplify_issue(&data, as_markdown);
+    let mut simplified = simplify_issue(data, as_markdown, full_users, config);
 
     // Inject media links into description [Media: filename] references
-    if as_markdown {
-        if let Some(obj) = simplified.as_object_mut() {
-            if let Some(Value::String(desc)) = obj.get("description") {
-                let desc_with_ids = inject_media_links(desc, &attachments);
-                obj.insert("description".to_string(), Value::String(desc_with_ids));
-            }
-        }
+    if as_markdown
+        && let Some(obj) = simplified.as_object_mut()
+        && let Some(Value::String(desc)) = obj.get("description")
+    {
+        let desc_with_ids = inject_media_links(desc, &attachments);
+        obj.insert("description".to_string(), Value::String(desc_with_ids));
     }
 
     // Add attachments to output
-    if let Some(obj) = simplified.as_object_mut() {
-        if !attachments.is_empty() {
-            obj.insert("attachments".to_string(), json!(attachments));
-        }
+    if let Some(obj) = simplified.as_object_mut()
+        && !attachments.is_empty()
+    {
+        obj.insert("attachments".to_string(), json!(attachments));
     }
 
     // Extract and add issuelinks
@@ -246,60 +569,243 @@ pub async fn get_issue(issue_key: &str, as_markdown: bool, config: &Config) -> R
         .filter(|link| !link.as_object().map(|o| o.is_empty()).unwrap_or(true))
         .collect();
 
-    if let Some(obj) = simplified.as_object_mut() {
-        if !issuelinks.is_empty() {
-            obj.insert("issuelinks".to_string(), json!(issuelinks));
-        }
+    if let Some(obj) = simplified.as_object_mut()
+        && !issuelinks.is_empty()
+    {
+        obj.insert("issuelinks".to_string(), json!(issuelinks));
     }
 
-    // Fetch and include comments at the end
-    let mut comments = fetch_comments_for_issue(issue_key, as_markdown, config).await;
+    let Some(mut comments) = comments else {
+        return simplified;
+    };
 
     // Inject media links into comment bodies
     if as_markdown {
-        for comment in &mut comments {
-            if let Some(Value::String(body)) = comment.get("body") {
-                let body_with_ids = inject_media_links(body, &attachments);
-                if let Some(obj) = comment.as_object_mut() {
-                    obj.insert("body".to_string(), Value::String(body_with_ids));
-                }
-            }
-        }
+        inject_media_links_into_comments(&mut comments, &attachments);
     }
 
     if let Some(obj) = simplified.as_object_mut() {
         obj.insert("comments".to_string(), json!(comments));
     }
 
-    Ok(simplified)
+    simplified
+}
+
+/// Fetch several issues with `key in (...)` searches (one per 100 keys) and return them
+/// in the requested order. Keys that don't resolve are listed under `missing`.
+pub async fn get_many(
+    issue_keys: &[String],
+    as_markdown: bool,
+    include_comments: bool,
+    include_attachments: bool,
+    full_users: bool,
+    config: &Config,
+) -> Result<Value> {
+    if issue_keys.is_empty() {
+        anyhow::bail!("No issue keys given");
+    }
+    validate_issue_keys(issue_keys)?;
+
+    let (requested_fields, _) = fields::resolve_get_fields(None, include_attachments, false);
+
+    let mut found: Vec<Value> = Vec::with_capacity(issue_keys.len());
+    for batch in issue_keys.chunks(MAX_RESULTS_PER_PAGE as usize) {
+        found.extend(search_key_batch(batch, &requested_fields, config).await?);
+    }
+
+    let (ordered, missing) = order_by_keys(issue_keys, found);
+    let mut items = Vec::with_capacity(ordered.len());
+    for data in &ordered {
+        let comments = if include_comments {
+            let key = data["key"].as_str().unwrap_or_default();
+            Some(fetch_comments_for_issue(key, as_markdown, config).await)
+        } else {
+            None
+        };
+        items.push(assemble_issue(
+            data,
+            comments,
+            as_markdown,
+            full_users,
+            config,
+        ));
+    }
+
+    Ok(json!({
+        "items": items,
+        "count": ordered.len(),
+        "missing": missing,
+    }))
+}
+
+/// `key in (...)` fails with 400 if any key doesn't exist; drop the keys named in the
+/// error and retry so the rest still resolve (the dropped ones end up in `missing`)
+async fn search_key_batch(
+    batch: &[String],
+    requested_fields: &[String],
+    config: &Config,
+) -> Result<Vec<Value>> {
+    let client = http::client(config);
+    let url = search_url(config);
+    let mut keys: Vec<&String> = batch.iter().collect();
+
+    while !keys.is_empty() {
+        let keys_jql: Vec<&str> = keys.iter().map(|k| k.as_str()).collect();
+        let body = json!({
+            "jql": format!("key in ({})", keys_jql.join(", ")),
+            "maxResults": MAX_RESULTS_PER_PAGE,
+            "fields": requested_fields,
+        });
+
+        let response = http::send_timed(
+            client
+                .post(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Content-Type", "application/json")
+                .json(&body),
+        )
+        .await?;
+
+        if response.status().is_success() {
+            let data: Value = response.json().await?;
+            return Ok(data["issues"].as_array().cloned().unwrap_or_default());
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let before = keys.len();
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            keys.retain(|k| !body.contains(&format!("'{}'", k)));
+        }
+        if keys.len() == before {
+            anyhow::bail!("Failed to get issues ({}): {}", status, body);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Keys are interpolated into JQL, so only accept `PROJ-123` keys or numeric ids
+fn validate_issue_keys(issue_keys: &[String]) -> Result<()> {
+    let is_key = |key: &str| {
+        key.split_once('-').is_some_and(|(project, number)| {
+            project.starts_with(|c: char| c.is_ascii_alphabetic())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }) || (!key.is_empty() && key.chars().all(|c| c.is_ascii_digit()))
+    };
+    if let Some(bad) = issue_keys.iter().find(|k| !is_key(k)) {
+        anyhow::bail!("Invalid issue key '{}': expected e.g. PROJ-123", bad);
+    }
+    Ok(())
+}
+
+/// Match search results back to the requested keys (case-insensitive, ids too)
+fn order_by_keys(issue_keys: &[String], found: Vec<Value>) -> (Vec<Value>, Vec<String>) {
+    let mut ordered = Vec::with_capacity(issue_keys.len());
+    let mut missing = Vec::new();
+    for key in issue_keys {
+        let issue = found.iter().find(|issue| {
+            issue["key"]
+                .as_str()
+                .is_some_and(|k| k.eq_ignore_ascii_case(key))
+                || issue["id"].as_str() == Some(key.as_str())
+        });
+        match issue {
+            Some(issue) => ordered.push(issue.clone()),
+            None => missing.push(key.clone()),
+        }
+    }
+    (ordered, missing)
+}
+
+/// Drop simplified keys whose source field wasn't requested with `--fields`, and add
+/// requested fields the simplified shape doesn't cover (e.g. custom fields) as raw values.
+fn retain_requested_fields(issue: &mut Value, data: &Value, requested: &[String]) {
+    let Some(obj) = issue.as_object_mut() else {
+        return;
+    };
+    fn source_field(key: &str) -> &str {
+        match key {
+            "type" => "issuetype",
+            "attachments" => "attachment",
+            other => other,
+        }
+    }
+
+    obj.retain(|key, _| {
+        key == "key" || key == "comments" || requested.iter().any(|f| f == source_field(key))
+    });
+
+    for field in requested {
+        let covered = obj.keys().any(|key| source_field(key) == field);
+        if !covered && let Some(value) = data["fields"].get(field) {
+            obj.insert(field.clone(), value.clone());
+        }
+    }
+}
+
+fn inject_media_links_into_comments(comments: &mut [Value], attachments: &[Value]) {
+    for comment in comments {
+        if let Some(Value::String(body)) = comment.get("body") {
+            let body_with_links = inject_media_links(body, attachments);
+            if let Some(obj) = comment.as_object_mut() {
+                obj.insert("body".to_string(), Value::String(body_with_links));
+            }
+        }
+    }
 }
 
+/// One page of results. Pass the returned `nextPageToken` back as `page_token` to
+/// continue; on Data Center the token is the next `startAt` offset.
 pub async fn search(
     jql: &str,
     limit: u32,
     fields: Option<Vec<String>>,
+    page_token: Option<&str>,
     as_markdown: bool,
     config: &Config,
 ) -> Result<Value> {
     let final_jql = apply_project_filter(jql, config);
+    validate_jql(&final_jql)?;
+    let limit = pagination::clamp_limit(limit, MAX_RESULTS_PER_PAGE, config.quiet);
     let client = http::client(config);
-    let url = format!("{}/rest/api/3/search/jql", config.base_url());
+    let url = search_url(config);
 
     let resolved_fields = fields::resolve_search_fields(fields, as_markdown, config);
 
-    let body = json!({
+    let mut body = json!({
         "jql": final_jql,
         "maxResults": limit,
         "fields": resolved_fields,
     });
+    let start_at = if config.is_datacenter() {
+        let start_at = match page_token {
+            Some(token) => token
+                .parse::<u64>()
+                .map_err(|_| anyhow::anyhow!("Invalid page token '{}'", token))?,
+            None => 0,
+        };
+        body["startAt"] = json!(start_at);
+        start_at
+    } else {
+        if let Some(token) = page_token {
+            body["nextPageToken"] = json!(token);
+        }
+        0
+    };
 
-    let response = client
-        .post(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .post(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -316,71 +822,282 @@ pub async fn search(
         "items": issues,
         "count": count
     });
+    if let Some(total) = data["total"].as_u64() {
+        result["total"] = json!(total);
+    }
+    let next_token = next_page_token(&data, start_at, count, config);
+    if !config.quiet
+        && let Some(warning) = pagination::truncation_warning(
+            limit,
+            count,
+            data["total"].as_u64(),
+            next_token.is_some(),
+        )
+    {
+        eprintln!("{}", warning);
+    }
+    if let Some(token) = next_token {
+        result["nextPageToken"] = json!(token);
+    }
 
     if as_markdown {
-        convert_issues_to_markdown(&mut result);
+        convert_issues_to_markdown(&mut result, config);
     }
 
     Ok(result)
 }
 
-pub async fn search_all(
+fn next_page_token(data: &Value, start_at: u64, count: usize, config: &Config) -> Option<String> {
+    if config.is_datacenter() {
+        let next = start_at + count as u64;
+        let total = data["total"].as_u64()?;
+        (count > 0 && next < total).then(|| next.to_string())
+    } else if is_last_page(data) {
+        None
+    } else {
+        data["nextPageToken"].as_str().map(String::from)
+    }
+}
+
+/// Cloud search may return a `nextPageToken` together with `isLast: true`; following
+/// that token only yields an empty page, so `isLast` wins when present
+fn is_last_page(data: &Value) -> bool {
+    data["isLast"].as_bool().unwrap_or(false)
+}
+
+/// Poll a JQL every `interval` and print issues not seen before as JSONL until Ctrl-C.
+/// The first poll only records the current matches; failed polls after that are
+/// reported on stderr and retried at the next interval.
+pub async fn watch(
     jql: &str,
+    interval: Duration,
     fields: Option<Vec<String>>,
-    stream: bool,
-    as_markdown: bool,
     config: &Config,
 ) -> Result<Value> {
-    let final_jql = apply_project_filter(jql, config);
-    let client = http::client(config);
-    let url = format!("{}/rest/api/3/search/jql", config.base_url());
-    let resolved_fields = fields::resolve_search_fields(fields, as_markdown, config);
-
-    let mut all_issues: Vec<Value> = Vec::new();
-    let mut page_num = 1;
-    let mut next_page_token: Option<String> = None;
-    let mut total_count: u64 = 0;
+    // Page progress from search_all would flood stderr on every poll
+    let mut poll_config = config.clone();
+    poll_config.quiet = true;
+    poll_config.progress_json = false;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let initial = search_all(
+        jql,
+        fields.clone(),
+        pagination::Sink::Collect,
+        pagination::Paging::default(),
+        false,
+        false,
+        &poll_config,
+    )
+    .await?;
+    let baseline = new_issues(&mut seen, &initial);
+    if !config.quiet {
+        eprintln!(
+            "Watching {} existing issues, polling every {}s (Ctrl-C to stop)",
+            baseline.len(),
+            interval.as_secs()
+        );
+    }
 
+    let mut emitted = 0usize;
     loop {
-        let mut body = json!({
-            "jql": final_jql,
-            "maxResults": MAX_RESULTS_PER_PAGE,
-            "fields": resolved_fields,
-        });
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
 
-        if let Some(ref token) = next_page_token {
-            body["nextPageToken"] = json!(token);
+        let poll = search_all(
+            jql,
+            fields.clone(),
+            pagination::Sink::Collect,
+            pagination::Paging::default(),
+            false,
+            false,
+            &poll_config,
+        );
+        let result = tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            result = poll => result,
+        };
+        match result {
+            Ok(result) => {
+                for issue in new_issues(&mut seen, &result) {
+                    println!("{}", serde_json::to_string(&issue)?);
+                    emitted += 1;
+                }
+                io::stdout().flush()?;
+            }
+            Err(e) => eprintln!("Warning: poll failed: {:#}", e),
         }
+    }
 
-        let response = client
-            .post(&url)
+    Ok(json!({"watched": true, "emitted": emitted}))
+}
+
+/// Issues from a search result whose keys are not in `seen`, recording them as seen
+fn new_issues(seen: &mut HashSet<String>, result: &Value) -> Vec<Value> {
+    result["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|issue| {
+            issue["key"]
+                .as_str()
+                .is_some_and(|key| seen.insert(key.to_string()))
+        })
+        .cloned()
+        .collect()
+}
+
+/// ADF for Cloud (REST API v3); Data Center (REST API v2) takes plain text / wiki markup
+fn rich_text_input(value: Value, field_name: &str, config: &Config) -> Result<Value> {
+    if config.is_datacenter() {
+        adf::process_wiki_input(value, field_name)
+    } else {
+        adf::process_adf_input(value, field_name)
+    }
+}
+
+/// Cloud's enhanced JQL search (`/search/jql`, token paging); Data Center only has `/search`
+fn search_url(config: &Config) -> String {
+    if config.is_datacenter() {
+        format!("{}/search", config.jira_api())
+    } else {
+        format!("{}/search/jql", config.jira_api())
+    }
+}
+
+/// Count matching issues without fetching them.
+/// `/search/jql` no longer reports `total`, so this uses the approximate-count endpoint
+/// (Data Center: `/search` with `maxResults: 0`, which still reports `total`).
+pub async fn count_issues(jql: &str, config: &Config) -> Result<Value> {
+    let final_jql = apply_project_filter(jql, config);
+    validate_jql(&final_jql)?;
+    let (conditions, _) = split_order_by(&final_jql);
+    let client = http::client(config);
+    let (url, body) = if config.is_datacenter() {
+        (
+            search_url(config),
+            json!({ "jql": conditions.trim(), "maxResults": 0 }),
+        )
+    } else {
+        (
+            format!("{}/search/approximate-count", config.jira_api()),
+            json!({ "jql": conditions.trim() }),
+        )
+    };
+
+    let response = http::send_timed(
+        client
+            .post(&url)
             .header("Authorization", http::auth_header(config))
             .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+            .json(&body),
+    )
+    .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Search failed ({}): {}", status, body);
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Count failed ({}): {}", status, body);
+    }
+
+    let data: Value = response.json().await?;
+    let total = data["count"].as_u64().or_else(|| data["total"].as_u64());
+    Ok(json!({ "total": total.unwrap_or(0) }))
+}
+
+pub async fn search_all(
+    jql: &str,
+    fields: Option<Vec<String>>,
+    mut sink: pagination::Sink,
+    paging: pagination::Paging,
+    as_markdown: bool,
+    partial_ok: bool,
+    config: &Config,
+) -> Result<Value> {
+    let final_jql = apply_project_filter(jql, config);
+    validate_jql(&final_jql)?;
+    let client = http::client(config);
+    let url = search_url(config);
+    let resolved_fields = fields::resolve_search_fields(fields, as_markdown, config);
+    let page_size = paging.page_size(MAX_RESULTS_PER_PAGE, config.quiet);
+    let max = paging.max;
+
+    let mut all_issues: Vec<Value> = Vec::new();
+    let mut fetched: usize = 0;
+    let mut page_num = 1;
+    let mut next_page_token: Option<String> = None;
+    let mut total_count: u64 = 0;
+    let mut errors: Vec<Value> = Vec::new();
+
+    loop {
+        let mut body = json!({
+            "jql": final_jql,
+            "maxResults": page_size,
+            "fields": resolved_fields,
+        });
+
+        if let Some(ref token) = next_page_token {
+            body["nextPageToken"] = json!(token);
+        }
+        if config.is_datacenter() {
+            body["startAt"] = json!(fetched);
         }
 
-        let mut data: Value = response.json().await?;
+        let page = async {
+            let response = http::send_timed(
+                client
+                    .post(&url)
+                    .header("Authorization", http::auth_header(config))
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Search failed ({}): {}", status, body);
+            }
+
+            Ok(response.json::<Value>().await?)
+        };
+
+        let mut data = match page.await {
+            Ok(data) => data,
+            // Keep what was already fetched instead of discarding it for one bad page
+            Err(e) if partial_ok => {
+                let error = json!({
+                    "page": page_num,
+                    "fetched": fetched,
+                    "error": format!("{:#}", e),
+                });
+                if config.progress_json {
+                    eprintln!("{}", error);
+                } else if !config.quiet {
+                    eprintln!("  Page {}: failed, stopping ({:#})", page_num, e);
+                }
+                errors.push(error);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
         filter::apply(&mut data, config);
 
         if page_num == 1 {
             total_count = data["total"].as_u64().unwrap_or(0);
         }
 
-        let issues = data["issues"].as_array().cloned().unwrap_or_default();
+        let mut issues = data["issues"].as_array().cloned().unwrap_or_default();
         let count = issues.len();
+        let capped = pagination::apply_cap(&mut issues, fetched, max);
 
         let processed_issues: Vec<Value> = if as_markdown {
             issues
                 .into_iter()
                 .map(|mut issue| {
-                    convert_issue_to_markdown(&mut issue);
+                    convert_issue_to_markdown(&mut issue, config);
                     issue
                 })
                 .collect()
@@ -388,81 +1105,80 @@ pub async fn search_all(
             issues
         };
 
-        if stream {
-            for issue in &processed_issues {
-                println!("{}", serde_json::to_string(issue)?);
-            }
-            io::stdout().flush()?;
-        }
-
-        all_issues.extend(processed_issues);
+        fetched += processed_issues.len();
+        // Only collected in memory when not streamed or exported
+        all_issues.extend(sink.write_page(processed_issues)?);
 
-        eprintln!(
-            "  Page {}: {} issues (fetched: {}/{})",
-            page_num,
-            count,
-            all_issues.len(),
-            total_count
-        );
+        pagination::report_page(config, page_num, "issues", count, fetched, total_count, max);
 
         next_page_token = data["nextPageToken"].as_str().map(String::from);
-        if next_page_token.is_none() || count == 0 {
+        // Data Center pages by offset against the reported total
+        let has_more = if config.is_datacenter() {
+            (fetched as u64) < total_count
+        } else {
+            next_page_token.is_some() && !is_last_page(&data)
+        };
+        if !has_more || count == 0 || capped {
             break;
         }
 
         page_num += 1;
-        sleep(Duration::from_millis(
-            config.performance.rate_limit_delay_ms,
-        ))
-        .await;
     }
 
-    eprintln!("\nTotal: {} issues fetched", all_issues.len());
+    if !config.quiet && !config.progress_json {
+        eprintln!("\nTotal: {} issues fetched", fetched);
+    }
 
-    if stream {
-        Ok(json!({"streamed": true, "total": all_issues.len()}))
-    } else {
-        Ok(json!({
-            "items": all_issues,
-            "total": all_issues.len()
-        }))
+    let mut result = sink.finish(all_issues, fetched)?;
+    if !errors.is_empty() {
+        result["complete"] = json!(false);
+        result["errors"] = json!(errors);
     }
+    Ok(result)
 }
 
+/// `extra_fields` (e.g. from a template) are sent alongside the core fields,
+/// which take precedence on conflicts
 pub async fn create_issue(
     project_key: &str,
     summary: &str,
     issue_type: &str,
     description: Value,
+    extra_fields: &serde_json::Map<String, Value>,
+    dedupe_minutes: Option<u64>,
     config: &Config,
 ) -> Result<Value> {
+    // Jira's create endpoint has no idempotency key, so retries are guarded by
+    // looking for an identical issue the current user created recently
+    if let Some(minutes) = dedupe_minutes
+        && let Some(existing) = find_recent_duplicate(project_key, summary, minutes, config).await?
+    {
+        return Ok(existing);
+    }
+
     let client = http::client(config);
-    let base_url = format!("{}/rest/api/3/issue", config.base_url());
+    let base_url = format!("{}/issue", config.jira_api());
 
     let url = fields::apply_field_filtering_to_url(&base_url);
 
-    let description_adf = adf::process_description_input(description)?;
+    let description_adf = rich_text_input(description, "description", config)?;
 
-    let body = json!({
-        "fields": {
-            "project": {
-                "key": project_key
-            },
-            "summary": summary,
-            "issuetype": {
-                "name": issue_type
-            },
-            "description": description_adf
-        }
-    });
+    let mut issue_fields = extra_fields.clone();
+    issue_fields.extend([
+        ("project".to_string(), json!({ "key": project_key })),
+        ("summary".to_string(), json!(summary)),
+        ("issuetype".to_string(), json!({ "name": issue_type })),
+        ("description".to_string(), description_adf),
+    ]);
+    let body = json!({ "fields": issue_fields });
 
-    let response = client
-        .post(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.post(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let error = response.text().await?;
@@ -476,31 +1192,189 @@ pub async fn create_issue(
     }))
 }
 
+/// One line of `jira create --from-stdin` input
+#[derive(Deserialize)]
+struct NdjsonIssue {
+    project: String,
+    summary: String,
+    #[serde(alias = "type")]
+    issue_type: String,
+    #[serde(default)]
+    description: Value,
+}
+
+/// Create one issue per NDJSON line, printing a result line (key and URL, or the
+/// error) for each input as it completes. Failures don't stop the remaining lines.
+pub async fn create_from_ndjson(
+    reader: impl BufRead,
+    dedupe_minutes: Option<u64>,
+    config: &Config,
+) -> Result<Value> {
+    let no_extra_fields = serde_json::Map::new();
+    let mut created = 0usize;
+    let mut failed = 0usize;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = match serde_json::from_str::<NdjsonIssue>(&line) {
+            Ok(issue) => {
+                create_issue(
+                    &issue.project,
+                    &issue.summary,
+                    &issue.issue_type,
+                    issue.description,
+                    &no_extra_fields,
+                    dedupe_minutes,
+                    config,
+                )
+                .await
+            }
+            Err(e) => Err(anyhow::anyhow!("Invalid input line: {}", e)),
+        };
+
+        let output = match result {
+            Ok(issue) => {
+                created += 1;
+                let key = issue["key"].as_str().unwrap_or_default();
+                json!({
+                    "line": index + 1,
+                    "key": key,
+                    "url": format!("{}/browse/{}", config.base_url(), key)
+                })
+            }
+            Err(e) => {
+                failed += 1;
+                json!({"line": index + 1, "error": format!("{:#}", e)})
+            }
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        io::stdout().flush()?;
+    }
+
+    if !config.quiet {
+        eprintln!("Created {} issues, {} failed", created, failed);
+    }
+    Ok(json!({"created": created, "failed": failed}))
+}
+
+/// One line of `jira bulk-update --from-stdin` input
+#[derive(Deserialize)]
+struct NdjsonUpdate {
+    key: String,
+    fields: serde_json::Map<String, Value>,
+}
+
+/// Update one issue per NDJSON line via `update_issue` (descriptions go through the same
+/// ADF handling), printing a result line for each input. Failures don't stop the remaining lines.
+pub async fn update_from_ndjson(reader: impl BufRead, config: &Config) -> Result<Value> {
+    let mut updated = 0usize;
+    let mut failed = 0usize;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let output = match serde_json::from_str::<NdjsonUpdate>(&line) {
+            Ok(update) => {
+                match update_issue(&update.key, Value::Object(update.fields), config).await {
+                    Ok(_) => {
+                        updated += 1;
+                        json!({"line": index + 1, "key": update.key, "updated": true})
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        json!({"line": index + 1, "key": update.key, "error": format!("{:#}", e)})
+                    }
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                json!({"line": index + 1, "error": format!("Invalid input line: {}", e)})
+            }
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        io::stdout().flush()?;
+    }
+
+    if !config.quiet {
+        eprintln!("Updated {} issues, {} failed", updated, failed);
+    }
+    Ok(json!({"updated": updated, "failed": failed}))
+}
+
+async fn find_recent_duplicate(
+    project_key: &str,
+    summary: &str,
+    minutes: u64,
+    config: &Config,
+) -> Result<Option<Value>> {
+    let jql = dedupe_jql(project_key, minutes);
+    let recent = search(
+        &jql,
+        50,
+        Some(vec!["summary".to_string()]),
+        None,
+        false,
+        config,
+    )
+    .await?;
+    let issues = recent["items"].as_array().cloned().unwrap_or_default();
+    Ok(pick_duplicate(&issues, summary))
+}
+
+fn dedupe_jql(project_key: &str, minutes: u64) -> String {
+    format!(
+        "project = \"{}\" AND reporter = currentUser() AND created >= -{}m ORDER BY created DESC",
+        project_key.replace('"', "\\\""),
+        minutes
+    )
+}
+
+/// Exact summary match among recent issues (JQL text search is fuzzy, so compare here)
+fn pick_duplicate(issues: &[Value], summary: &str) -> Option<Value> {
+    issues
+        .iter()
+        .find(|issue| issue["fields"]["summary"].as_str() == Some(summary))
+        .map(|issue| {
+            json!({
+                "key": issue["key"],
+                "id": issue["id"],
+                "deduplicated": true
+            })
+        })
+}
+
 pub async fn update_issue(
     issue_key: &str,
     mut fields_value: Value,
     config: &Config,
 ) -> Result<Value> {
     let client = http::client(config);
-    let url = format!("{}/rest/api/3/issue/{}", config.base_url(), issue_key);
+    let url = format!("{}/issue/{}", config.jira_api(), issue_key);
 
     if let Some(fields_obj) = fields_value.as_object_mut()
         && let Some(description_ref) = fields_obj.get_mut("description")
     {
         let description = std::mem::replace(description_ref, Value::Null);
-        let description_adf = adf::process_description_input(description)?;
+        let description_adf = rich_text_input(description, "description", config)?;
         fields_obj.insert("description".to_string(), description_adf);
     }
 
-    let response = client
-        .put(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&json!({
-            "fields": fields_value
-        }))
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.put(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "fields": fields_value
+            })),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -511,13 +1385,73 @@ pub async fn update_issue(
     Ok(json!({}))
 }
 
-fn simplify_comment(comment: &Value, as_markdown: bool) -> Value {
+/// Add and remove labels with Jira's `update` operations, leaving other labels untouched.
+/// Returns the resulting label set.
+pub async fn update_labels(
+    issue_key: &str,
+    add: &[String],
+    remove: &[String],
+    config: &Config,
+) -> Result<Value> {
+    if add.is_empty() && remove.is_empty() {
+        anyhow::bail!("No label changes given: pass --add and/or --remove");
+    }
+
+    let client = http::client(config);
+    let url = format!("{}/issue/{}", config.jira_api(), issue_key);
+
+    let response = http::send_timed(
+        http::no_check(client.put(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "update": {"labels": label_operations(add, remove)}
+            })),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to update labels ({}): {}", status, body);
+    }
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .query(&[("fields", "labels")]),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get labels ({}): {}", status, body);
+    }
+
+    let data: Value = response.json().await?;
+    Ok(json!({
+        "key": data["key"],
+        "labels": data["fields"]["labels"]
+    }))
+}
+
+fn label_operations(add: &[String], remove: &[String]) -> Vec<Value> {
+    add.iter()
+        .map(|label| json!({"add": label}))
+        .chain(remove.iter().map(|label| json!({"remove": label})))
+        .collect()
+}
+
+fn simplify_comment(comment: &Value, as_markdown: bool, config: &Config) -> Value {
     let body = if as_markdown {
         comment
             .get("body")
             .map(|b| {
                 if b.is_object() {
-                    Value::String(adf_to_markdown(b))
+                    Value::String(adf_to_markdown(b, config))
                 } else {
                     b.clone()
                 }
@@ -538,18 +1472,24 @@ fn simplify_comment(comment: &Value, as_markdown: bool) -> Value {
 
 pub async fn get_comments(issue_key: &str, as_markdown: bool, config: &Config) -> Result<Value> {
     let client = http::client(config);
-    let url = format!(
-        "{}/rest/api/3/issue/{}/comment",
-        config.base_url(),
-        issue_key
-    );
+    let url = format!("{}/issue/{}/comment", config.jira_api(), issue_key);
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+    let request = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    );
+    // Attachments are only needed for media links in markdown output
+    let attachments = async {
+        if as_markdown {
+            fetch_attachments_for_issue(issue_key, config).await
+        } else {
+            vec![]
+        }
+    };
+    let (response, attachments) = tokio::join!(request, attachments);
+    let response = response?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -562,22 +1502,11 @@ pub async fn get_comments(issue_key: &str, as_markdown: bool, config: &Config) -
 
     let mut processed_comments: Vec<Value> = comments
         .iter()
-        .map(|comment| simplify_comment(comment, as_markdown))
+        .map(|comment| simplify_comment(comment, as_markdown, config))
         .collect();
 
-    // Fetch attachments and inject media links into comment bodies
-    if as_markdown {
-        let attachments = fetch_attachments_for_issue(issue_key, config).await;
-        if !attachments.is_empty() {
-            for comment in &mut processed_comments {
-                if let Some(Value::String(body)) = comment.get("body") {
-                    let body_with_links = inject_media_links(body, &attachments);
-                    if let Some(obj) = comment.as_object_mut() {
-                        obj.insert("body".to_string(), Value::String(body_with_links));
-                    }
-                }
-            }
-        }
+    if !attachments.is_empty() {
+        inject_media_links_into_comments(&mut processed_comments, &attachments);
     }
 
     Ok(json!({
@@ -586,21 +1515,69 @@ pub async fn get_comments(issue_key: &str, as_markdown: bool, config: &Config) -
     }))
 }
 
+/// A single comment, in the same shape as an entry of `get_comments`
+pub async fn get_comment(
+    issue_key: &str,
+    comment_id: &str,
+    as_markdown: bool,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!(
+        "{}/issue/{}/comment/{}",
+        config.jira_api(),
+        issue_key,
+        comment_id
+    );
+
+    let request = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    );
+    let attachments = async {
+        if as_markdown {
+            fetch_attachments_for_issue(issue_key, config).await
+        } else {
+            vec![]
+        }
+    };
+    let (response, attachments) = tokio::join!(request, attachments);
+    let response = response?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get comment ({}): {}", status, body);
+    }
+
+    let data: Value = response.json().await?;
+    let mut comment = [simplify_comment(&data, as_markdown, config)];
+    if !attachments.is_empty() {
+        inject_media_links_into_comments(&mut comment, &attachments);
+    }
+
+    let [comment] = comment;
+    Ok(comment)
+}
+
 /// Fetch only the attachment field for an issue (lightweight request for media link injection)
 async fn fetch_attachments_for_issue(issue_key: &str, config: &Config) -> Vec<Value> {
     let client = http::client(config);
     let url = format!(
-        "{}/rest/api/3/issue/{}?fields=attachment",
-        config.base_url(),
+        "{}/issue/{}?fields=attachment",
+        config.jira_api(),
         issue_key
     );
 
-    let response = match client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await
+    let response = match http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await
     {
         Ok(r) => r,
         Err(_) => return vec![],
@@ -621,20 +1598,21 @@ async fn fetch_attachments_for_issue(issue_key: &str, config: &Config) -> Vec<Va
         .unwrap_or_default()
 }
 
-async fn fetch_comments_for_issue(issue_key: &str, as_markdown: bool, config: &Config) -> Vec<Value> {
+async fn fetch_comments_for_issue(
+    issue_key: &str,
+    as_markdown: bool,
+    config: &Config,
+) -> Vec<Value> {
     let client = http::client(config);
-    let url = format!(
-        "{}/rest/api/3/issue/{}/comment",
-        config.base_url(),
-        issue_key
-    );
+    let url = format!("{}/issue/{}/comment", config.jira_api(), issue_key);
 
-    let response = match client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await
+    let response = match http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await
     {
         Ok(r) => r,
         Err(_) => return vec![],
@@ -654,19 +1632,15 @@ async fn fetch_comments_for_issue(issue_key: &str, as_markdown: bool, config: &C
         .cloned()
         .unwrap_or_default()
         .iter()
-        .map(|comment| simplify_comment(comment, as_markdown))
+        .map(|comment| simplify_comment(comment, as_markdown, config))
         .collect()
 }
 
 pub async fn add_comment(issue_key: &str, comment: Value, config: &Config) -> Result<Value> {
-    let comment_adf = adf::process_comment_input(comment)?;
+    let comment_adf = rich_text_input(comment, "comment", config)?;
 
     let client = http::client(config);
-    let base_url = format!(
-        "{}/rest/api/3/issue/{}/comment",
-        config.base_url(),
-        issue_key
-    );
+    let base_url = format!("{}/issue/{}/comment", config.jira_api(), issue_key);
 
     let url = fields::apply_field_filtering_to_url(&base_url);
 
@@ -674,13 +1648,13 @@ pub async fn add_comment(issue_key: &str, comment: Value, config: &Config) -> Re
         "body": comment_adf
     });
 
-    let response = client
-        .post(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.post(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -698,12 +1672,12 @@ pub async fn update_comment(
     body: Value,
     config: &Config,
 ) -> Result<Value> {
-    let body_adf = adf::process_comment_input(body)?;
+    let body_adf = rich_text_input(body, "comment", config)?;
 
     let client = http::client(config);
     let base_url = format!(
-        "{}/rest/api/3/issue/{}/comment/{}",
-        config.base_url(),
+        "{}/issue/{}/comment/{}",
+        config.jira_api(),
         issue_key,
         comment_id
     );
@@ -714,13 +1688,13 @@ pub async fn update_comment(
         "body": body_adf
     });
 
-    let response = client
-        .put(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.put(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&request_body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let error = response.text().await?;
@@ -737,11 +1711,7 @@ pub async fn transition_issue(
     config: &Config,
 ) -> Result<Value> {
     let client = http::client(config);
-    let url = format!(
-        "{}/rest/api/3/issue/{}/transitions",
-        config.base_url(),
-        issue_key
-    );
+    let url = format!("{}/issue/{}/transitions", config.jira_api(), issue_key);
 
     let body = json!({
         "transition": {
@@ -749,13 +1719,13 @@ pub async fn transition_issue(
         }
     });
 
-    let response = client
-        .post(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let response = http::send_timed(
+        http::no_check(client.post(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -766,196 +1736,1201 @@ pub async fn transition_issue(
     Ok(json!({}))
 }
 
-pub async fn get_transitions(issue_key: &str, config: &Config) -> Result<Value> {
+/// List transitions. With `with_fields`, the transition screens are expanded and each
+/// transition is simplified to `{id, name, to, required_fields}`.
+pub async fn get_transitions(issue_key: &str, with_fields: bool, config: &Config) -> Result<Value> {
     let client = http::client(config);
-    let base_url = format!(
-        "{}/rest/api/3/issue/{}/transitions",
-        config.base_url(),
-        issue_key
+    let base_url = format!("{}/issue/{}/transitions", config.jira_api(), issue_key);
+
+    let url = if with_fields {
+        format!("{}?expand=transitions.fields", base_url)
+    } else {
+        fields::apply_field_filtering_to_url(&base_url)
+    };
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get transitions ({}): {}", status, body);
+    }
+
+    let mut data: Value = response.json().await?;
+    if with_fields {
+        let transitions = data["transitions"].as_array().cloned().unwrap_or_default();
+        return Ok(json!(
+            transitions
+                .iter()
+                .map(simplify_transition)
+                .collect::<Vec<_>>()
+        ));
+    }
+    filter::apply(&mut data, config);
+    Ok(data["transitions"].take())
+}
+
+fn simplify_transition(transition: &Value) -> Value {
+    let required_fields: Vec<Value> = transition["fields"]
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(_, field)| field["required"].as_bool() == Some(true))
+                .map(|(key, field)| {
+                    let mut entry = json!({"key": key, "name": field["name"]});
+                    if let Some(allowed) = field["allowedValues"].as_array() {
+                        let values: Vec<Value> = allowed
+                            .iter()
+                            .filter_map(|v| v.get("name").or_else(|| v.get("value")).cloned())
+                            .collect();
+                        entry["allowed_values"] = json!(values);
+                    }
+                    entry
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "id": transition["id"],
+        "name": transition["name"],
+        "to": transition["to"]["name"],
+        "required_fields": required_fields,
+    })
+}
+
+/// Change history of an issue as a flat timeline, oldest first.
+/// Cloud pages through `/changelog`; Data Center only offers `expand=changelog`.
+pub async fn get_changelog(issue_key: &str, field: Option<&str>, config: &Config) -> Result<Value> {
+    let histories = if config.is_datacenter() {
+        fetch_expanded_changelog(issue_key, config).await?
+    } else {
+        fetch_changelog_pages(issue_key, config).await?
+    };
+
+    let entries = changelog_entries(&histories, field);
+    Ok(json!({
+        "key": issue_key,
+        "changelog": entries,
+        "count": entries.len()
+    }))
+}
+
+async fn fetch_changelog_pages(issue_key: &str, config: &Config) -> Result<Vec<Value>> {
+    let client = http::client(config);
+    let url = format!("{}/issue/{}/changelog", config.jira_api(), issue_key);
+
+    let mut histories: Vec<Value> = Vec::new();
+    let mut start_at: usize = 0;
+
+    loop {
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&[
+                    ("startAt", start_at.to_string()),
+                    ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+                ]),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get changelog ({}): {}", status, body);
+        }
+
+        let data: Value = response.json().await?;
+        let values = data["values"].as_array().cloned().unwrap_or_default();
+        let count = values.len();
+        histories.extend(values);
+
+        if data["isLast"].as_bool().unwrap_or(true) || count == 0 {
+            break;
+        }
+
+        start_at += count;
+    }
+
+    Ok(histories)
+}
+
+async fn fetch_expanded_changelog(issue_key: &str, config: &Config) -> Result<Vec<Value>> {
+    let client = http::client(config);
+    let url = format!(
+        "{}/issue/{}?fields=none&expand=changelog",
+        config.jira_api(),
+        issue_key
     );
 
-    let url = fields::apply_field_filtering_to_url(&base_url);
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get changelog ({}): {}", status, body);
+    }
+
+    let mut data: Value = response.json().await?;
+    let mut histories = match data["changelog"]["histories"].take() {
+        Value::Array(histories) => histories,
+        _ => vec![],
+    };
+    // The expanded changelog is newest first; the paged endpoint is oldest first
+    histories.sort_by(|a, b| a["created"].as_str().cmp(&b["created"].as_str()));
+    Ok(histories)
+}
+
+/// One entry per changed field, optionally limited to a single field (case-insensitive)
+fn changelog_entries(histories: &[Value], field: Option<&str>) -> Vec<Value> {
+    histories
+        .iter()
+        .flat_map(|history| {
+            let author = history["author"]
+                .get("displayName")
+                .cloned()
+                .unwrap_or(Value::Null);
+            let created = history.get("created").cloned().unwrap_or(Value::Null);
+            history["items"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(move |item| {
+                    field.is_none_or(|f| {
+                        ["field", "fieldId"]
+                            .iter()
+                            .any(|k| item[*k].as_str().is_some_and(|v| v.eq_ignore_ascii_case(f)))
+                    })
+                })
+                .map(move |item| {
+                    json!({
+                        "author": author,
+                        "created": created,
+                        "field": item["field"],
+                        "from": change_value(item, "fromString", "from"),
+                        "to": change_value(item, "toString", "to"),
+                    })
+                })
+        })
+        .collect()
+}
+
+/// Display value of one side of a change, falling back to the raw ID
+fn change_value(item: &Value, display_key: &str, raw_key: &str) -> Value {
+    [display_key, raw_key]
+        .iter()
+        .find_map(|k| item.get(*k).filter(|v| !v.is_null()).cloned())
+        .unwrap_or(Value::Null)
+}
+
+/// Fields of the create screen for a project and issue type (name or ID): which are
+/// required, their types and the allowed values of select fields
+pub async fn get_create_meta(
+    project_key: &str,
+    issue_type: &str,
+    config: &Config,
+) -> Result<Value> {
+    let base_url = format!(
+        "{}/issue/createmeta/{}/issuetypes",
+        config.jira_api(),
+        project_key
+    );
+
+    let issue_types = fetch_create_meta_pages(&base_url, "issue types", config).await?;
+    let Some(matched) = find_issue_type(&issue_types, issue_type) else {
+        let mut names: Vec<&str> = issue_types
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        names.sort_unstable();
+        anyhow::bail!(
+            "Issue type '{}' not found in project {}. Available: {}",
+            issue_type,
+            project_key,
+            names.join(", ")
+        );
+    };
+    let type_id = matched["id"].as_str().unwrap_or_default();
+
+    let fields_url = format!("{}/{}", base_url, type_id);
+    let fields: Vec<Value> = fetch_create_meta_pages(&fields_url, "create metadata", config)
+        .await?
+        .iter()
+        .map(simplify_create_field)
+        .collect();
+    let required: Vec<Value> = fields
+        .iter()
+        .filter(|f| f["required"].as_bool() == Some(true))
+        .map(|f| f["key"].clone())
+        .collect();
+
+    Ok(json!({
+        "project": project_key,
+        "issue_type": {"id": matched["id"], "name": matched["name"]},
+        "required": required,
+        "fields": fields,
+    }))
+}
+
+/// All entries of a paged createmeta endpoint. Cloud lists them under `issueTypes` /
+/// `fields`, Data Center under `values`.
+async fn fetch_create_meta_pages(url: &str, what: &str, config: &Config) -> Result<Vec<Value>> {
+    let client = http::client(config);
+    let mut entries: Vec<Value> = Vec::new();
+    let mut start_at: usize = 0;
+
+    loop {
+        let response = http::send_timed(
+            client
+                .get(url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&[
+                    ("startAt", start_at.to_string()),
+                    ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+                ]),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get {} ({}): {}", what, status, body);
+        }
+
+        let data: Value = response.json().await?;
+        let page = ["issueTypes", "fields", "values"]
+            .iter()
+            .find_map(|k| data[*k].as_array())
+            .cloned()
+            .unwrap_or_default();
+        let count = page.len();
+        entries.extend(page);
+
+        let total = data["total"].as_u64().unwrap_or(0) as usize;
+        if count == 0 || data["isLast"].as_bool() == Some(true) || entries.len() >= total {
+            break;
+        }
+        start_at += count;
+    }
+
+    Ok(entries)
+}
+
+fn find_issue_type<'a>(issue_types: &'a [Value], name_or_id: &str) -> Option<&'a Value> {
+    issue_types.iter().find(|t| {
+        t["id"].as_str() == Some(name_or_id)
+            || t["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(name_or_id))
+    })
+}
+
+fn simplify_create_field(field: &Value) -> Value {
+    let schema = &field["schema"];
+    let field_type = match (schema["type"].as_str(), schema["items"].as_str()) {
+        (Some("array"), Some(items)) => Value::String(format!("array<{}>", items)),
+        (Some(t), _) => Value::String(t.to_string()),
+        _ => Value::Null,
+    };
+
+    let mut entry = json!({
+        "key": field.get("fieldId").or_else(|| field.get("key")).cloned().unwrap_or(Value::Null),
+        "name": field["name"],
+        "required": field["required"].as_bool().unwrap_or(false),
+        "type": field_type,
+    });
+    if let Some(custom) = schema["custom"].as_str() {
+        entry["custom"] = json!(custom);
+    }
+    if let Some(allowed) = field["allowedValues"].as_array() {
+        let values: Vec<Value> = allowed
+            .iter()
+            .filter_map(|v| v.get("name").or_else(|| v.get("value")).cloned())
+            .collect();
+        if !values.is_empty() {
+            entry["allowed_values"] = json!(values);
+        }
+    }
+    if field["hasDefaultValue"].as_bool() == Some(true) {
+        entry["has_default"] = json!(true);
+    }
+    entry
+}
+
+pub async fn get_attachments(issue_key: &str, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!(
+        "{}/issue/{}?fields=attachment",
+        config.jira_api(),
+        issue_key
+    );
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get attachments ({}): {}", status, body);
+    }
+
+    let data: Value = response.json().await?;
+    let attachments = data["fields"]["attachment"].clone();
+
+    if attachments.is_null() {
+        return Ok(json!([]));
+    }
+
+    Ok(attachments)
+}
+
+pub async fn upload_attachment(issue_key: &str, file: &Path, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/issue/{}/attachments", config.jira_api(), issue_key);
+
+    let filename = file
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", file.display()))?
+        .to_string();
+    let bytes = std::fs::read(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+
+    let part = reqwest::multipart::Part::bytes(bytes).file_name(filename);
+    let form = reqwest::multipart::Form::new().part("file", part);
+
+    let response = http::send_timed(
+        client
+            .post(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json")
+            .header("X-Atlassian-Token", "no-check")
+            .multipart(form),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to upload attachment ({}): {}", status, body);
+    }
+
+    // Jira responds with an array of the created attachments
+    let data: Value = response.json().await?;
+    let attachment = data
+        .as_array()
+        .and_then(|a| a.first())
+        .ok_or_else(|| anyhow::anyhow!("Upload response contained no attachment"))?;
+
+    Ok(json!({
+        "id": attachment.get("id").cloned().unwrap_or(Value::Null),
+        "filename": attachment.get("filename").cloned().unwrap_or(Value::Null),
+        "size": attachment.get("size").cloned().unwrap_or(Value::Null),
+    }))
+}
+
+pub async fn download_attachment(
+    attachment_id: &str,
+    output_path: Option<&Path>,
+    overwrite: bool,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+
+    // First, get attachment metadata to get the content URL and filename
+    let meta_url = format!("{}/attachment/{}", config.jira_api(), attachment_id);
+
+    let meta_response = http::send_timed(
+        client
+            .get(&meta_url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !meta_response.status().is_success() {
+        let status = meta_response.status();
+        let body = meta_response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get attachment metadata ({}): {}", status, body);
+    }
+
+    let metadata: Value = meta_response.json().await?;
+    let content_url = metadata["content"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("No content URL in attachment metadata"))?;
+    let filename = metadata["filename"].as_str().unwrap_or("attachment");
+
+    // Determine output path before downloading, so a collision fails fast
+    let final_path = attachment::resolve_output_path(output_path, filename, overwrite)?;
+
+    // Download the actual file content
+    let content_response = http::send_timed(
+        client
+            .get(content_url)
+            .header("Authorization", http::auth_header(config)),
+    )
+    .await?;
+
+    if !content_response.status().is_success() {
+        let status = content_response.status();
+        let body = content_response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to download attachment ({}): {}", status, body);
+    }
+
+    let bytes = content_response.bytes().await?;
+
+    // Write to file
+    std::fs::write(&final_path, &bytes)?;
+
+    Ok(json!({
+        "filename": filename,
+        "path": final_path.to_string_lossy(),
+        "size": bytes.len(),
+        "id": attachment_id
+    }))
+}
+
+pub async fn delete_attachment(attachment_id: &str, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/attachment/{}", config.jira_api(), attachment_id);
+
+    let response = http::send_timed(
+        client
+            .delete(&url)
+            .header("X-Atlassian-Token", "no-check")
+            .header("Authorization", http::auth_header(config)),
+    )
+    .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        match status {
+            reqwest::StatusCode::FORBIDDEN => anyhow::bail!(
+                "Failed to delete attachment ({}): you don't have permission to delete attachment {}: {}",
+                status,
+                attachment_id,
+                body
+            ),
+            reqwest::StatusCode::NOT_FOUND => anyhow::bail!(
+                "Failed to delete attachment ({}): attachment {} not found (or attachments are disabled): {}",
+                status,
+                attachment_id,
+                body
+            ),
+            _ => anyhow::bail!("Failed to delete attachment ({}): {}", status, body),
+        }
+    }
+
+    Ok(json!({"deleted": attachment_id}))
+}
+
+pub async fn get_myself(config: &Config) -> Result<Value> {
+    Ok(simplify_user(&fetch_current_user(config).await?))
+}
+
+async fn fetch_current_user(config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/myself", config.jira_api());
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to get current user ({}): {}", status, body);
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Assign an issue to the current user (`jira take`)
+pub async fn assign_to_me(issue_key: &str, config: &Config) -> Result<Value> {
+    let me = fetch_current_user(config).await?;
+    // Data Center identifies users by name, Cloud by accountId
+    let body = if config.is_datacenter() {
+        json!({"name": me["name"]})
+    } else {
+        json!({"accountId": me["accountId"]})
+    };
+
+    let client = http::client(config);
+    let url = format!("{}/issue/{}/assignee", config.jira_api(), issue_key);
+    let response = http::send_timed(
+        http::no_check(client.put(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to assign issue ({}): {}", status, body);
+    }
+
+    Ok(json!({"key": issue_key, "assignee": me["displayName"]}))
+}
+
+/// Move an issue into progress (`jira start`) via the first transition matching
+/// `[jira.workflow] start` (by transition or target status name)
+pub async fn start_issue(issue_key: &str, config: &Config) -> Result<Value> {
+    let transitions = get_transitions(issue_key, false, config).await?;
+    let transitions = transitions.as_array().cloned().unwrap_or_default();
+    let names = config.jira.workflow.start_names();
+
+    let Some(transition) = find_transition(&transitions, &names) else {
+        let available: Vec<&str> = transitions
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        anyhow::bail!(
+            "No transition matching {} on {} (available: {}); set [jira.workflow] start",
+            names.join(" / "),
+            issue_key,
+            available.join(", ")
+        );
+    };
+    let id = transition["id"].as_str().unwrap_or_default();
+    transition_issue(issue_key, id, config).await?;
+
+    Ok(json!({
+        "key": issue_key,
+        "transition": transition["name"],
+        "status": transition["to"]["name"],
+    }))
+}
+
+/// First transition, in order of preference of `names`, whose name or target status
+/// matches case-insensitively
+fn find_transition<'a>(transitions: &'a [Value], names: &[String]) -> Option<&'a Value> {
+    names.iter().find_map(|wanted| {
+        transitions.iter().find(|t| {
+            [t["name"].as_str(), t["to"]["name"].as_str()]
+                .into_iter()
+                .flatten()
+                .any(|n| n.eq_ignore_ascii_case(wanted))
+        })
+    })
+}
+
+pub async fn search_users(query: &str, limit: u32, all: bool, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let encoded_query: String = query
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' {
+                c.to_string()
+            } else {
+                format!("%{:02X}", c as u8)
+            }
+        })
+        .collect();
+
+    let mut users: Vec<Value> = Vec::new();
+    let mut start_at: u32 = 0;
+
+    loop {
+        let page = fetch_users_page(&client, &encoded_query, start_at, limit, config).await?;
+        let count = page.len();
+        users.extend(page.iter().map(simplify_user));
+
+        // The endpoint returns a bare array, so a short page marks the end
+        if !all || is_last_user_page(count, limit) {
+            break;
+        }
+
+        start_at += count as u32;
+    }
+
+    Ok(json!({
+        "users": users,
+        "count": users.len()
+    }))
+}
+
+async fn fetch_users_page(
+    client: &Client,
+    encoded_query: &str,
+    start_at: u32,
+    max_results: u32,
+    config: &Config,
+) -> Result<Vec<Value>> {
+    let url = format!(
+        "{}/user/search?query={}&startAt={}&maxResults={}",
+        config.jira_api(),
+        encoded_query,
+        start_at,
+        max_results
+    );
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("User search failed ({}): {}", status, body);
+    }
+
+    let data: Value = response.json().await?;
+    Ok(data.as_array().cloned().unwrap_or_default())
+}
+
+fn is_last_user_page(count: usize, page_size: u32) -> bool {
+    count == 0 || count < page_size as usize
+}
+
+/// Sprints of a board, optionally limited to `state` (active, future, closed; comma-separated)
+pub async fn get_sprints(board_id: u64, state: Option<&str>, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/board/{}/sprint", config.jira_agile_api(), board_id);
+
+    let mut sprints: Vec<Value> = Vec::new();
+    let mut start_at: usize = 0;
+
+    loop {
+        let mut query = vec![
+            ("startAt", start_at.to_string()),
+            ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+        ];
+        if let Some(state) = state {
+            query.push(("state", state.to_string()));
+        }
+
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&query),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get sprints ({}): {}", status, body);
+        }
+
+        let data: Value = response.json().await?;
+        let values = data["values"].as_array().cloned().unwrap_or_default();
+        let count = values.len();
+        sprints.extend(values.iter().map(simplify_sprint));
+
+        if data["isLast"].as_bool().unwrap_or(true) || count == 0 {
+            break;
+        }
+
+        start_at += count;
+    }
+
+    Ok(json!({
+        "sprints": sprints,
+        "count": sprints.len()
+    }))
+}
+
+fn simplify_sprint(sprint: &Value) -> Value {
+    json!({
+        "id": sprint.get("id").cloned().unwrap_or(Value::Null),
+        "name": sprint.get("name").cloned().unwrap_or(Value::Null),
+        "state": sprint.get("state").cloned().unwrap_or(Value::Null),
+        "startDate": sprint.get("startDate").cloned().unwrap_or(Value::Null),
+        "endDate": sprint.get("endDate").cloned().unwrap_or(Value::Null),
+    })
+}
+
+/// All issues in a sprint, with the same field selection as `search`
+pub async fn get_sprint_issues(
+    sprint_id: u64,
+    fields: Option<Vec<String>>,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/sprint/{}/issue", config.jira_agile_api(), sprint_id);
+    let resolved_fields = fields::resolve_search_fields(fields, false, config).join(",");
+
+    let mut issues: Vec<Value> = Vec::new();
+    loop {
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&[
+                    ("startAt", issues.len().to_string()),
+                    ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+                    ("fields", resolved_fields.clone()),
+                ]),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get sprint issues ({}): {}", status, body);
+        }
+
+        let mut data: Value = response.json().await?;
+        filter::apply(&mut data, config);
+
+        let page = data["issues"].as_array().cloned().unwrap_or_default();
+        let count = page.len();
+        issues.extend(page);
+
+        let total = data["total"].as_u64().unwrap_or(0);
+        if count == 0 || issues.len() as u64 >= total {
+            break;
+        }
+    }
+
+    Ok(json!({
+        "items": issues,
+        "count": issues.len()
+    }))
+}
+
+/// The agile API moves at most 50 issues per request
+const SPRINT_MOVE_BATCH: usize = 50;
+
+/// Move issues into a sprint (removing them from their current sprint)
+pub async fn move_to_sprint(
+    sprint_id: u64,
+    issue_keys: &[String],
+    config: &Config,
+) -> Result<Value> {
+    if issue_keys.is_empty() {
+        anyhow::bail!("No issue keys given");
+    }
+    validate_issue_keys(issue_keys)?;
+
+    let client = http::client(config);
+    let url = format!("{}/sprint/{}/issue", config.jira_agile_api(), sprint_id);
+
+    for batch in issue_keys.chunks(SPRINT_MOVE_BATCH) {
+        let response = http::send_timed(
+            http::no_check(client.post(&url), config)
+                .header("Authorization", http::auth_header(config))
+                .header("Content-Type", "application/json")
+                .json(&json!({ "issues": batch })),
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to move issues to sprint ({}): {}", status, body);
+        }
+    }
+
+    Ok(json!({
+        "sprint": sprint_id,
+        "moved": issue_keys,
+        "count": issue_keys.len()
+    }))
+}
+
+/// List visible projects, limited to `projects_filter` when set
+pub async fn get_projects(config: &Config) -> Result<Value> {
+    let client = http::client(config);
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
+    let mut projects: Vec<Value> = Vec::new();
+    if config.is_datacenter() {
+        // Data Center has no paginated project search; /project returns everything
+        let url = format!("{}/project", config.jira_api());
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&[("expand", "lead")]),
+        )
         .await?;
 
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get projects ({}): {}", status, body);
+        }
+
+        let data: Value = response.json().await?;
+        projects.extend(data.as_array().into_iter().flatten().map(simplify_project));
+    } else {
+        let url = format!("{}/project/search", config.jira_api());
+        let mut start_at: usize = 0;
+
+        loop {
+            let response = http::send_timed(
+                client
+                    .get(&url)
+                    .header("Authorization", http::auth_header(config))
+                    .header("Accept", "application/json")
+                    .query(&[
+                        ("startAt", start_at.to_string()),
+                        ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+                        ("expand", "lead".to_string()),
+                    ]),
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to get projects ({}): {}", status, body);
+            }
+
+            let data: Value = response.json().await?;
+            let values = data["values"].as_array().cloned().unwrap_or_default();
+            let count = values.len();
+            projects.extend(values.iter().map(simplify_project));
+
+            if data["isLast"].as_bool().unwrap_or(true) || count == 0 {
+                break;
+            }
+
+            start_at += count;
+        }
+    }
+
+    let projects = filter_projects(projects, &config.jira.projects_filter);
+
+    Ok(json!({
+        "projects": projects,
+        "count": projects.len()
+    }))
+}
+
+fn simplify_project(project: &Value) -> Value {
+    json!({
+        "key": project.get("key").cloned().unwrap_or(Value::Null),
+        "name": project.get("name").cloned().unwrap_or(Value::Null),
+        "projectTypeKey": project.get("projectTypeKey").cloned().unwrap_or(Value::Null),
+        "lead": project["lead"].get("displayName").cloned().unwrap_or(Value::Null),
+    })
+}
+
+/// Get one project with its lead, issue types, components and versions in a single call
+pub async fn get_project(project_key: &str, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/project/{}", config.jira_api(), project_key);
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
+
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to get transitions ({}): {}", status, body);
+        anyhow::bail!("Failed to get project ({}): {}", status, body);
     }
 
-    let mut data: Value = response.json().await?;
-    filter::apply(&mut data, config);
-    Ok(data["transitions"].take())
+    let data: Value = response.json().await?;
+    Ok(simplify_project_detail(&data, config))
 }
 
-pub async fn get_attachments(issue_key: &str, config: &Config) -> Result<Value> {
-    let client = http::client(config);
-    let url = format!(
-        "{}/rest/api/3/issue/{}?fields=attachment",
-        config.base_url(),
-        issue_key
+fn simplify_project_detail(project: &Value, config: &Config) -> Value {
+    let names = |key: &str| -> Vec<Value> {
+        project[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|item| {
+                json!({
+                    "id": item.get("id").cloned().unwrap_or(Value::Null),
+                    "name": item.get("name").cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect()
+    };
+    let issue_types: Vec<Value> = project["issueTypes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|t| {
+            json!({
+                "id": t.get("id").cloned().unwrap_or(Value::Null),
+                "name": t.get("name").cloned().unwrap_or(Value::Null),
+                "subtask": t.get("subtask").cloned().unwrap_or(Value::Bool(false)),
+            })
+        })
+        .collect();
+    let versions: Vec<Value> = project["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(simplify_version)
+        .collect();
+    let key = project["key"].as_str().unwrap_or_default();
+
+    let mut detail = simplify_project(project);
+    let obj = detail
+        .as_object_mut()
+        .expect("simplify_project returns an object");
+    obj.insert(
+        "description".to_string(),
+        project.get("description").cloned().unwrap_or(Value::Null),
     );
+    obj.insert("issueTypes".to_string(), Value::Array(issue_types));
+    obj.insert("components".to_string(), Value::Array(names("components")));
+    obj.insert("versions".to_string(), Value::Array(versions));
+    obj.insert(
+        "url".to_string(),
+        json!(format!("{}/browse/{}", config.base_url(), key)),
+    );
+    detail
+}
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+fn filter_projects(projects: Vec<Value>, projects_filter: &[String]) -> Vec<Value> {
+    if projects_filter.is_empty() {
+        return projects;
+    }
+    projects
+        .into_iter()
+        .filter(|p| {
+            p["key"]
+                .as_str()
+                .is_some_and(|key| projects_filter.iter().any(|f| f.eq_ignore_ascii_case(key)))
+        })
+        .collect()
+}
+
+pub async fn get_components(project_key: &str, config: &Config) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/project/{}/components", config.jira_api(), project_key);
+
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to get attachments ({}): {}", status, body);
+        anyhow::bail!("Failed to get components ({}): {}", status, body);
     }
 
     let data: Value = response.json().await?;
-    let attachments = data["fields"]["attachment"].clone();
-
-    if attachments.is_null() {
-        return Ok(json!([]));
-    }
+    let components: Vec<Value> = data
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .map(|c| {
+                    json!({
+                        "id": c.get("id").cloned().unwrap_or(Value::Null),
+                        "name": c.get("name").cloned().unwrap_or(Value::Null),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
 
-    Ok(attachments)
+    Ok(json!({
+        "components": components,
+        "count": components.len()
+    }))
 }
 
-pub async fn download_attachment(
-    attachment_id: &str,
-    output_path: Option<&Path>,
-    config: &Config,
-) -> Result<Value> {
+pub async fn get_versions(project_key: &str, config: &Config) -> Result<Value> {
     let client = http::client(config);
+    let url = format!("{}/project/{}/version", config.jira_api(), project_key);
 
-    // First, get attachment metadata to get the content URL and filename
-    let meta_url = format!(
-        "{}/rest/api/3/attachment/{}",
-        config.base_url(),
-        attachment_id
-    );
+    let mut versions: Vec<Value> = Vec::new();
+    let mut start_at: usize = 0;
 
-    let meta_response = client
-        .get(&meta_url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
+    loop {
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json")
+                .query(&[
+                    ("startAt", start_at.to_string()),
+                    ("maxResults", MAX_RESULTS_PER_PAGE.to_string()),
+                ]),
+        )
         .await?;
 
-    if !meta_response.status().is_success() {
-        let status = meta_response.status();
-        let body = meta_response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to get attachment metadata ({}): {}", status, body);
-    }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get versions ({}): {}", status, body);
+        }
 
-    let metadata: Value = meta_response.json().await?;
-    let content_url = metadata["content"]
-        .as_str()
-        .ok_or_else(|| anyhow::anyhow!("No content URL in attachment metadata"))?;
-    let filename = metadata["filename"]
-        .as_str()
-        .unwrap_or("attachment");
+        let data: Value = response.json().await?;
+        let values = data["values"].as_array().cloned().unwrap_or_default();
+        let count = values.len();
+        versions.extend(values.iter().map(simplify_version));
 
-    // Download the actual file content
-    let content_response = client
-        .get(content_url)
-        .header("Authorization", http::auth_header(config))
-        .send()
-        .await?;
+        if data["isLast"].as_bool().unwrap_or(true) || count == 0 {
+            break;
+        }
 
-    if !content_response.status().is_success() {
-        let status = content_response.status();
-        let body = content_response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to download attachment ({}): {}", status, body);
+        start_at += count;
     }
 
-    let bytes = content_response.bytes().await?;
+    Ok(json!({
+        "versions": versions,
+        "count": versions.len()
+    }))
+}
 
-    // Determine output path
-    let final_path = match output_path {
-        Some(p) => p.to_path_buf(),
-        None => std::env::current_dir()?.join(filename),
-    };
+fn simplify_version(version: &Value) -> Value {
+    json!({
+        "id": version.get("id").cloned().unwrap_or(Value::Null),
+        "name": version.get("name").cloned().unwrap_or(Value::Null),
+        "released": version.get("released").cloned().unwrap_or(Value::Bool(false)),
+        "archived": version.get("archived").cloned().unwrap_or(Value::Bool(false)),
+    })
+}
 
-    // Write to file
-    std::fs::write(&final_path, &bytes)?;
+/// Create a version unless one with the same name exists; the existing one is returned
+/// instead (`created: false`), or reported as an error with `strict`
+pub async fn create_version(
+    project_key: &str,
+    name: &str,
+    strict: bool,
+    config: &Config,
+) -> Result<Value> {
+    let existing = get_versions(project_key, config).await?;
+    if let Some(version) = find_by_name(&existing["versions"], name) {
+        if strict {
+            anyhow::bail!(
+                "Version '{}' already exists in {} (id {})",
+                name,
+                project_key,
+                version["id"].as_str().unwrap_or_default()
+            );
+        }
+        return Ok(json!({"created": false, "version": version}));
+    }
+
+    let created = post_project_entity("version", project_key, name, config).await?;
+    Ok(json!({"created": true, "version": simplify_version(&created)}))
+}
+
+/// Create a component unless one with the same name exists; the existing one is returned
+/// instead (`created: false`), or reported as an error with `strict`
+pub async fn create_component(
+    project_key: &str,
+    name: &str,
+    strict: bool,
+    config: &Config,
+) -> Result<Value> {
+    let existing = get_components(project_key, config).await?;
+    if let Some(component) = find_by_name(&existing["components"], name) {
+        if strict {
+            anyhow::bail!(
+                "Component '{}' already exists in {} (id {})",
+                name,
+                project_key,
+                component["id"].as_str().unwrap_or_default()
+            );
+        }
+        return Ok(json!({"created": false, "component": component}));
+    }
 
+    let created = post_project_entity("component", project_key, name, config).await?;
     Ok(json!({
-        "filename": filename,
-        "path": final_path.to_string_lossy(),
-        "size": bytes.len(),
-        "id": attachment_id
+        "created": true,
+        "component": {
+            "id": created.get("id").cloned().unwrap_or(Value::Null),
+            "name": created.get("name").cloned().unwrap_or(Value::Null),
+        }
     }))
 }
 
-pub async fn search_users(query: &str, limit: u32, config: &Config) -> Result<Value> {
-    let client = http::client(config);
-    let encoded_query: String = query
-        .chars()
-        .map(|c| {
-            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' {
-                c.to_string()
-            } else {
-                format!("%{:02X}", c as u8)
-            }
+/// Jira treats version and component names as case-insensitive within a project
+fn find_by_name(items: &Value, name: &str) -> Option<Value> {
+    items
+        .as_array()?
+        .iter()
+        .find(|item| {
+            item["name"]
+                .as_str()
+                .is_some_and(|n| n.trim().eq_ignore_ascii_case(name.trim()))
         })
-        .collect();
-    let url = format!(
-        "{}/rest/api/3/user/search?query={}&maxResults={}",
-        config.base_url(),
-        encoded_query,
-        limit
-    );
+        .cloned()
+}
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+async fn post_project_entity(
+    entity: &str,
+    project_key: &str,
+    name: &str,
+    config: &Config,
+) -> Result<Value> {
+    let client = http::client(config);
+    let url = format!("{}/{}", config.jira_api(), entity);
+
+    let response = http::send_timed(
+        http::no_check(client.post(&url), config)
+            .header("Authorization", http::auth_header(config))
+            .header("Content-Type", "application/json")
+            .json(&json!({"project": project_key, "name": name})),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("User search failed ({}): {}", status, body);
+        anyhow::bail!("Failed to create {} ({}): {}", entity, status, body);
     }
 
-    let data: Value = response.json().await?;
-    let users: Vec<Value> = data
-        .as_array()
-        .cloned()
-        .unwrap_or_default()
-        .iter()
-        .map(|user| {
-            json!({
-                "accountId": user.get("accountId").cloned().unwrap_or(Value::Null),
-                "displayName": user.get("displayName").cloned().unwrap_or(Value::Null),
-                "emailAddress": user.get("emailAddress").cloned().unwrap_or(Value::Null),
-                "active": user.get("active").cloned().unwrap_or(Value::Null),
-            })
-        })
-        .collect();
-
-    Ok(json!({
-        "users": users,
-        "count": users.len()
-    }))
+    Ok(response.json().await?)
 }
 
 pub async fn get_filters(config: &Config) -> Result<Value> {
     let client = http::client(config);
-    let url = format!("{}/rest/api/3/filter/favourite", config.base_url());
+    let url = format!("{}/filter/favourite", config.jira_api());
 
-    let response = client
-        .get(&url)
-        .header("Authorization", http::auth_header(config))
-        .header("Accept", "application/json")
-        .send()
-        .await?;
+    let response = http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", http::auth_header(config))
+            .header("Accept", "application/json"),
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -990,18 +2965,15 @@ pub async fn get_filter(filter_id_or_name: &str, config: &Config) -> Result<Valu
     // If it looks like a numeric ID, fetch directly
     if filter_id_or_name.chars().all(|c| c.is_ascii_digit()) {
         let client = http::client(config);
-        let url = format!(
-            "{}/rest/api/3/filter/{}",
-            config.base_url(),
-            filter_id_or_name
-        );
-
-        let response = client
-            .get(&url)
-            .header("Authorization", http::auth_header(config))
-            .header("Accept", "application/json")
-            .send()
-            .await?;
+        let url = format!("{}/filter/{}", config.jira_api(), filter_id_or_name);
+
+        let response = http::send_timed(
+            client
+                .get(&url)
+                .header("Authorization", http::auth_header(config))
+                .header("Accept", "application/json"),
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1021,10 +2993,10 @@ pub async fn get_filter(filter_id_or_name: &str, config: &Config) -> Result<Valu
 
     let name_lower = filter_id_or_name.to_lowercase();
     for item in items {
-        if let Some(name) = item.get("name").and_then(|n| n.as_str()) {
-            if name.to_lowercase() == name_lower {
-                return Ok(item.clone());
-            }
+        if let Some(name) = item.get("name").and_then(|n| n.as_str())
+            && name.to_lowercase() == name_lower
+        {
+            return Ok(item.clone());
         }
     }
 
@@ -1042,7 +3014,7 @@ pub async fn get_filter(filter_id_or_name: &str, config: &Config) -> Result<Valu
 mod tests {
     use super::*;
 
-    use crate::test_utils::create_test_config_with_filters;
+    use crate::test_utils::{create_test_config_with_filters, serve, serve_once, use_stub};
 
     // Helper function to create test config
     fn create_test_config(
@@ -1341,7 +3313,7 @@ mod tests {
         let config = create_test_config(vec![], None);
         let issue_key = "PROJ-123";
 
-        let base_url = format!("{}/rest/api/3/issue/{}", config.base_url(), issue_key);
+        let base_url = format!("{}/issue/{}", config.jira_api(), issue_key);
 
         assert_eq!(
             base_url,
@@ -1410,7 +3382,7 @@ mod tests {
         let config = create_test_config(vec![], None);
         let issue_key = "PROJ-123";
 
-        let url = format!("{}/rest/api/3/issue/{}", config.base_url(), issue_key);
+        let url = format!("{}/issue/{}", config.jira_api(), issue_key);
 
         assert_eq!(url, "https://test.atlassian.net/rest/api/3/issue/PROJ-123");
     }
@@ -1445,80 +3417,291 @@ mod tests {
                 }]
             }
         });
-
-        assert_eq!(adf_body["body"]["type"], "doc");
-        assert_eq!(adf_body["body"]["version"], 1);
-        assert_eq!(adf_body["body"]["content"][0]["type"], "paragraph");
+
+        assert_eq!(adf_body["body"]["type"], "doc");
+        assert_eq!(adf_body["body"]["version"], 1);
+        assert_eq!(adf_body["body"]["content"][0]["type"], "paragraph");
+        assert_eq!(
+            adf_body["body"]["content"][0]["content"][0]["text"],
+            "This is a test comment"
+        );
+    }
+
+    #[test]
+    fn test_add_comment_url_construction() {
+        let config = create_test_config(vec![], None);
+        let issue_key = "PROJ-123";
+
+        let base_url = format!("{}/issue/{}/comment", config.jira_api(), issue_key);
+
+        assert_eq!(
+            base_url,
+            "https://test.atlassian.net/rest/api/3/issue/PROJ-123/comment"
+        );
+    }
+
+    // transition_issue tests
+    #[test]
+    fn test_transition_issue_valid_params() {
+        let issue_key = "PROJ-123";
+        let transition_id = "21";
+
+        assert_eq!(issue_key, "PROJ-123");
+        assert_eq!(transition_id, "21");
+    }
+
+    #[test]
+    fn test_transition_issue_body_format() {
+        let transition_id = "31";
+
+        let body = json!({
+            "transition": {
+                "id": transition_id
+            }
+        });
+
+        assert_eq!(body["transition"]["id"], "31");
+    }
+
+    // get_transitions tests
+    #[test]
+    fn test_get_transitions_valid_issue_key() {
+        let issue_key = "PROJ-123";
+        assert_eq!(issue_key, "PROJ-123");
+    }
+
+    #[test]
+    fn test_get_transitions_url_construction() {
+        let config = create_test_config(vec![], None);
+        let issue_key = "PROJ-123";
+
+        let base_url = format!("{}/issue/{}/transitions", config.jira_api(), issue_key);
+
+        assert_eq!(
+            base_url,
+            "https://test.atlassian.net/rest/api/3/issue/PROJ-123/transitions"
+        );
+    }
+
+    #[test]
+    fn test_simplify_transition_required_fields() {
+        let transition = json!({
+            "id": "31",
+            "name": "Resolve",
+            "to": {"id": "5", "name": "Resolved"},
+            "fields": {
+                "resolution": {
+                    "required": true,
+                    "name": "Resolution",
+                    "allowedValues": [{"id": "1", "name": "Fixed"}, {"id": "2", "name": "Won't Do"}]
+                },
+                "comment": {"required": false, "name": "Comment"}
+            }
+        });
         assert_eq!(
-            adf_body["body"]["content"][0]["content"][0]["text"],
-            "This is a test comment"
+            simplify_transition(&transition),
+            json!({
+                "id": "31",
+                "name": "Resolve",
+                "to": "Resolved",
+                "required_fields": [{
+                    "key": "resolution",
+                    "name": "Resolution",
+                    "allowed_values": ["Fixed", "Won't Do"]
+                }]
+            })
         );
     }
 
+    // get_many tests
     #[test]
-    fn test_add_comment_url_construction() {
-        let config = create_test_config(vec![], None);
-        let issue_key = "PROJ-123";
+    fn test_order_by_keys_preserves_request_order() {
+        let keys: Vec<String> = ["b-2", "A-1", "C-3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let found = vec![
+            json!({"id": "10", "key": "A-1"}),
+            json!({"id": "20", "key": "B-2"}),
+        ];
+        let (ordered, missing) = order_by_keys(&keys, found);
+        assert_eq!(ordered[0]["key"], "B-2");
+        assert_eq!(ordered[1]["key"], "A-1");
+        assert_eq!(missing, vec!["C-3"]);
+    }
 
-        let base_url = format!(
-            "{}/rest/api/3/issue/{}/comment",
-            config.base_url(),
-            issue_key
-        );
+    #[test]
+    fn test_validate_issue_keys() {
+        let ok: Vec<String> = ["PROJ-1", "AB_2-30", "10001"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(validate_issue_keys(&ok).is_ok());
+        for bad in ["PROJ", "-1", "PROJ-1) OR (x", "1PROJ-2", ""] {
+            assert!(validate_issue_keys(&[bad.to_string()]).is_err(), "{}", bad);
+        }
+    }
 
+    // group_issues tests
+    #[test]
+    fn test_group_issues_by_status() {
+        let result = json!({"items": [
+            {"key": "A-1", "fields": {"status": {"name": "Open"}}},
+            {"key": "A-2", "fields": {"status": {"name": "In Progress"}}},
+            {"key": "A-3", "fields": {"status": {"name": "Open"}}}
+        ]});
+        let grouped = group_issues(&result, GroupBy::Status);
         assert_eq!(
-            base_url,
-            "https://test.atlassian.net/rest/api/3/issue/PROJ-123/comment"
+            serde_json::to_string(&grouped).unwrap(),
+            r#"{"groups":{"Open":2,"In Progress":1},"total":3}"#
         );
     }
 
-    // transition_issue tests
     #[test]
-    fn test_transition_issue_valid_params() {
-        let issue_key = "PROJ-123";
-        let transition_id = "21";
+    fn test_group_issues_unassigned() {
+        let result = json!({"items": [
+            {"key": "A-1", "fields": {"assignee": null}},
+            {"key": "A-2", "fields": {"assignee": {"displayName": "Kim"}}}
+        ]});
+        let grouped = group_issues(&result, GroupBy::Assignee);
+        assert_eq!(grouped["groups"], json!({"Kim": 1, "Unassigned": 1}));
+    }
 
-        assert_eq!(issue_key, "PROJ-123");
-        assert_eq!(transition_id, "21");
+    #[test]
+    fn test_group_by_parse() {
+        assert_eq!("Type".parse::<GroupBy>().unwrap(), GroupBy::Type);
+        let err = "sprint".parse::<GroupBy>().unwrap_err().to_string();
+        assert!(err.contains("status, assignee, type or priority"));
     }
 
+    // create dedupe tests
     #[test]
-    fn test_transition_issue_body_format() {
-        let transition_id = "31";
+    fn test_dedupe_jql() {
+        assert_eq!(
+            dedupe_jql("PROJ", 10),
+            "project = \"PROJ\" AND reporter = currentUser() AND created >= -10m ORDER BY created DESC"
+        );
+    }
 
-        let body = json!({
-            "transition": {
-                "id": transition_id
-            }
-        });
+    #[test]
+    fn test_pick_duplicate_exact_summary() {
+        let issues = vec![
+            json!({"key": "PROJ-2", "id": "2", "fields": {"summary": "Fix login bug (follow-up)"}}),
+            json!({"key": "PROJ-1", "id": "1", "fields": {"summary": "Fix login bug"}}),
+        ];
+        assert_eq!(
+            pick_duplicate(&issues, "Fix login bug"),
+            Some(json!({"key": "PROJ-1", "id": "1", "deduplicated": true}))
+        );
+        assert_eq!(pick_duplicate(&issues, "Fix login"), None);
+    }
 
-        assert_eq!(body["transition"]["id"], "31");
+    // label_operations tests
+    #[test]
+    fn test_label_operations() {
+        let ops = label_operations(
+            &["backend".to_string(), "urgent".to_string()],
+            &["triage".to_string()],
+        );
+        assert_eq!(
+            json!(ops),
+            json!([{"add": "backend"}, {"add": "urgent"}, {"remove": "triage"}])
+        );
     }
 
-    // get_transitions tests
+    // assemble_issue tests
     #[test]
-    fn test_get_transitions_valid_issue_key() {
-        let issue_key = "PROJ-123";
-        assert_eq!(issue_key, "PROJ-123");
+    fn test_assemble_issue_merges_comments_and_attachments() {
+        let config = create_test_config(vec![], None);
+        let data = json!({
+            "key": "TEST-1",
+            "fields": {
+                "summary": "Title",
+                "description": "See [Media: shot.png]",
+                "attachment": [{
+                    "id": "10001",
+                    "filename": "shot.png",
+                    "content": "https://test.atlassian.net/attachment/10001"
+                }]
+            }
+        });
+        let comments = vec![json!({"id": "1", "body": "Also [Media: shot.png]"})];
+
+        let result = assemble_issue(&data, Some(comments), true, false, &config);
+        assert_eq!(result["key"], "TEST-1");
+        assert_eq!(result["attachments"][0]["id"], "10001");
+        assert_eq!(
+            result["description"],
+            "See ![shot.png](https://test.atlassian.net/attachment/10001)"
+        );
+        assert_eq!(
+            result["comments"][0]["body"],
+            "Also ![shot.png](https://test.atlassian.net/attachment/10001)"
+        );
     }
 
     #[test]
-    fn test_get_transitions_url_construction() {
+    fn test_retain_requested_fields() {
         let config = create_test_config(vec![], None);
-        let issue_key = "PROJ-123";
+        let data = json!({
+            "key": "TEST-1",
+            "fields": {
+                "status": {"name": "Done"},
+                "issuetype": {"name": "Bug"},
+                "customfield_10015": "2024-01-01"
+            }
+        });
+        let mut issue = assemble_issue(&data, None, false, false, &config);
+        let requested: Vec<String> = ["status", "issuetype", "customfield_10015"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        retain_requested_fields(&mut issue, &data, &requested);
 
-        let base_url = format!(
-            "{}/rest/api/3/issue/{}/transitions",
-            config.base_url(),
-            issue_key
+        assert_eq!(
+            issue,
+            json!({
+                "key": "TEST-1",
+                "type": "Bug",
+                "status": "Done",
+                "customfield_10015": "2024-01-01"
+            })
         );
+    }
+
+    #[test]
+    fn test_append_expanded_keeps_expand_sections() {
+        let data = json!({
+            "expand": "renderedFields,changelog",
+            "id": "10001",
+            "key": "TEST-1",
+            "fields": {"summary": "Title"},
+            "renderedFields": {"description": "<p>Hi</p>"},
+            "changelog": {"histories": []}
+        });
+        let mut issue = json!({"key": "TEST-1", "summary": "Title"});
+        append_expanded(&mut issue, &data);
 
         assert_eq!(
-            base_url,
-            "https://test.atlassian.net/rest/api/3/issue/PROJ-123/transitions"
+            issue,
+            json!({
+                "key": "TEST-1",
+                "summary": "Title",
+                "renderedFields": {"description": "<p>Hi</p>"},
+                "changelog": {"histories": []}
+            })
         );
     }
 
+    #[test]
+    fn test_assemble_issue_without_comments() {
+        let config = create_test_config(vec![], None);
+        let data = json!({"key": "TEST-1", "fields": {"summary": "Title"}});
+        let result = assemble_issue(&data, None, false, false, &config);
+        assert!(result.get("comments").is_none());
+        assert!(result.get("attachments").is_none());
+    }
+
     // inject_media_links tests
     #[test]
     fn test_inject_media_links_single_match() {
@@ -1603,4 +3786,569 @@ mod tests {
 
         assert_eq!(result, "[Media: screenshot.png]");
     }
+
+    #[test]
+    fn test_simplify_user() {
+        let user = json!({
+            "self": "https://test.atlassian.net/rest/api/3/user?accountId=abc",
+            "accountId": "abc",
+            "displayName": "Jane Doe",
+            "emailAddress": "jane@example.com",
+            "timeZone": "Asia/Seoul",
+            "active": true,
+            "avatarUrls": {"48x48": "url"}
+        });
+
+        let result = simplify_user(&user);
+        assert_eq!(result["accountId"], "abc");
+        assert_eq!(result["timeZone"], "Asia/Seoul");
+        assert_eq!(result["active"], true);
+        assert!(result.get("avatarUrls").is_none());
+    }
+
+    #[test]
+    fn test_upload_attachment_url_construction() {
+        let config = create_test_config(vec![], None);
+        let url = format!("{}/issue/{}/attachments", config.jira_api(), "TEST-123");
+        assert_eq!(
+            url,
+            "https://test.atlassian.net/rest/api/3/issue/TEST-123/attachments"
+        );
+    }
+
+    #[test]
+    fn test_parse_jql_duration() {
+        assert_eq!(parse_jql_duration("7d").unwrap(), "-7d");
+        assert_eq!(parse_jql_duration("24h").unwrap(), "-24h");
+        assert_eq!(parse_jql_duration("2w").unwrap(), "-2w");
+        assert_eq!(parse_jql_duration("30m").unwrap(), "-30m");
+        assert!(parse_jql_duration("d").is_err());
+        assert!(parse_jql_duration("7x").is_err());
+        assert!(parse_jql_duration("-7d").is_err());
+        assert!(parse_jql_duration("7일").is_err());
+    }
+
+    #[test]
+    fn test_apply_since_filters() {
+        assert_eq!(
+            apply_since_filters("status = Open", None, Some("7d")).unwrap(),
+            "(status = Open) AND updated >= -7d"
+        );
+        assert_eq!(
+            apply_since_filters(
+                "status = Open ORDER BY updated DESC",
+                Some("1w"),
+                Some("24h")
+            )
+            .unwrap(),
+            "(status = Open) AND created >= -1w AND updated >= -24h ORDER BY updated DESC"
+        );
+        assert_eq!(
+            apply_since_filters("ORDER BY created", Some("7d"), None).unwrap(),
+            "created >= -7d ORDER BY created"
+        );
+        assert_eq!(
+            apply_since_filters("status = Open", None, None).unwrap(),
+            "status = Open"
+        );
+    }
+
+    #[test]
+    fn test_apply_order() {
+        let orders = |specs: &[&str]| specs.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            apply_order("status = Open", &orders(&["updated:desc"])).unwrap(),
+            "status = Open ORDER BY updated DESC"
+        );
+        assert_eq!(
+            apply_order(
+                "status = Open ORDER BY created ASC",
+                &orders(&["priority:DESC", "key"])
+            )
+            .unwrap(),
+            "status = Open ORDER BY priority DESC, key ASC"
+        );
+        assert_eq!(
+            apply_order("", &orders(&["rank:asc"])).unwrap(),
+            "ORDER BY rank ASC"
+        );
+        assert_eq!(
+            apply_order("status = Open ORDER BY key", &[]).unwrap(),
+            "status = Open ORDER BY key"
+        );
+        assert!(apply_order("status = Open", &orders(&["updated:sideways"])).is_err());
+        assert!(apply_order("status = Open", &orders(&[":desc"])).is_err());
+    }
+
+    #[test]
+    fn test_is_last_user_page() {
+        assert!(is_last_user_page(0, 50));
+        assert!(is_last_user_page(12, 50));
+        assert!(!is_last_user_page(50, 50));
+    }
+
+    #[test]
+    fn test_simplify_version() {
+        let version = json!({
+            "self": "https://test.atlassian.net/rest/api/3/version/10000",
+            "id": "10000",
+            "name": "1.0",
+            "released": true,
+            "projectId": 10001
+        });
+
+        let result = simplify_version(&version);
+        assert_eq!(
+            result,
+            json!({"id": "10000", "name": "1.0", "released": true, "archived": false})
+        );
+    }
+
+    #[test]
+    fn test_simplify_project_detail() {
+        let config = create_test_config(vec![], None);
+        let project = json!({
+            "key": "PROJ", "name": "Project", "projectTypeKey": "software",
+            "description": "Main project",
+            "lead": {"displayName": "Jane Doe", "accountId": "abc"},
+            "issueTypes": [
+                {"id": "1", "name": "Bug", "subtask": false, "iconUrl": "x"},
+                {"id": "2", "name": "Sub-task", "subtask": true}
+            ],
+            "components": [{"id": "10", "name": "Backend", "self": "x"}],
+            "versions": [{"id": "20", "name": "1.0", "released": true}]
+        });
+
+        let detail = simplify_project_detail(&project, &config);
+        assert_eq!(detail["key"], "PROJ");
+        assert_eq!(detail["lead"], "Jane Doe");
+        assert_eq!(detail["description"], "Main project");
+        assert_eq!(
+            detail["issueTypes"],
+            json!([
+                {"id": "1", "name": "Bug", "subtask": false},
+                {"id": "2", "name": "Sub-task", "subtask": true}
+            ])
+        );
+        assert_eq!(
+            detail["components"],
+            json!([{"id": "10", "name": "Backend"}])
+        );
+        assert_eq!(
+            detail["versions"],
+            json!([{"id": "20", "name": "1.0", "released": true, "archived": false}])
+        );
+        assert_eq!(detail["url"], format!("{}/browse/PROJ", config.base_url()));
+    }
+
+    #[test]
+    fn test_simplify_and_filter_projects() {
+        let projects: Vec<Value> = [
+            json!({"key": "PROJ", "name": "Project", "projectTypeKey": "software",
+                   "lead": {"displayName": "Jane Doe"}, "avatarUrls": {}}),
+            json!({"key": "OPS", "name": "Operations", "projectTypeKey": "service_desk"}),
+        ]
+        .iter()
+        .map(simplify_project)
+        .collect();
+
+        assert_eq!(
+            projects[0],
+            json!({"key": "PROJ", "name": "Project", "projectTypeKey": "software", "lead": "Jane Doe"})
+        );
+        assert_eq!(projects[1]["lead"], Value::Null);
+
+        assert_eq!(filter_projects(projects.clone(), &[]).len(), 2);
+        let filtered = filter_projects(projects, &["proj".to_string()]);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0]["key"], "PROJ");
+    }
+
+    #[test]
+    fn test_new_issues_only_returns_unseen_keys() {
+        let mut seen = HashSet::new();
+        let first = json!({"items": [{"key": "A-1"}, {"key": "A-2"}]});
+        assert_eq!(new_issues(&mut seen, &first).len(), 2);
+
+        let second = json!({"items": [{"key": "A-2"}, {"key": "A-3"}, {"id": "no-key"}]});
+        let fresh = new_issues(&mut seen, &second);
+        assert_eq!(fresh, vec![json!({"key": "A-3"})]);
+        assert!(new_issues(&mut seen, &second).is_empty());
+    }
+
+    #[test]
+    fn test_next_page_token() {
+        let cloud = create_test_config(vec![], None);
+        let data = json!({"issues": [], "nextPageToken": "abc"});
+        assert_eq!(
+            next_page_token(&data, 0, 1, &cloud),
+            Some("abc".to_string())
+        );
+        assert_eq!(next_page_token(&json!({}), 0, 1, &cloud), None);
+        let last = json!({"issues": [], "nextPageToken": "abc", "isLast": true});
+        assert_eq!(next_page_token(&last, 0, 1, &cloud), None);
+
+        let mut dc = create_test_config(vec![], None);
+        dc.deployment = crate::config::Deployment::DataCenter;
+        let data = json!({"total": 120});
+        assert_eq!(next_page_token(&data, 0, 50, &dc), Some("50".to_string()));
+        assert_eq!(next_page_token(&data, 100, 20, &dc), None);
+    }
+
+    #[test]
+    fn test_simplify_sprint() {
+        let sprint = json!({
+            "id": 37,
+            "self": "https://test.atlassian.net/rest/agile/1.0/sprint/37",
+            "state": "active",
+            "name": "Sprint 12",
+            "startDate": "2024-05-01T09:00:00.000Z",
+            "endDate": "2024-05-15T09:00:00.000Z",
+            "originBoardId": 5
+        });
+        assert_eq!(
+            simplify_sprint(&sprint),
+            json!({
+                "id": 37,
+                "name": "Sprint 12",
+                "state": "active",
+                "startDate": "2024-05-01T09:00:00.000Z",
+                "endDate": "2024-05-15T09:00:00.000Z"
+            })
+        );
+    }
+
+    #[test]
+    fn test_changelog_entries_flatten_and_filter() {
+        let histories = vec![
+            json!({
+                "author": {"displayName": "Alice"},
+                "created": "2024-01-01T10:00:00.000+0000",
+                "items": [
+                    {"field": "status", "fieldId": "status", "from": "1", "fromString": "To Do", "to": "3", "toString": "In Progress"},
+                    {"field": "assignee", "fieldId": "assignee", "from": null, "fromString": null, "to": "abc", "toString": "Bob"}
+                ]
+            }),
+            json!({
+                "author": {"displayName": "Bob"},
+                "created": "2024-01-02T10:00:00.000+0000",
+                "items": [
+                    {"field": "Status", "fieldId": "status", "fromString": "In Progress", "toString": "Done"}
+                ]
+            }),
+        ];
+
+        let all = changelog_entries(&histories, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1]["field"], "assignee");
+        assert_eq!(all[1]["from"], Value::Null);
+        assert_eq!(all[1]["to"], "Bob");
+
+        let status = changelog_entries(&histories, Some("STATUS"));
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[0]["author"], "Alice");
+        assert_eq!(status[0]["from"], "To Do");
+        assert_eq!(status[1]["to"], "Done");
+        assert_eq!(status[1]["created"], "2024-01-02T10:00:00.000+0000");
+    }
+
+    #[test]
+    fn test_ndjson_issue_line() {
+        let issue: NdjsonIssue = serde_json::from_str(
+            r#"{"project": "PROJ", "summary": "From ETL", "issue_type": "Task"}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.project, "PROJ");
+        assert_eq!(issue.issue_type, "Task");
+        assert!(issue.description.is_null());
+
+        let issue: NdjsonIssue = serde_json::from_str(
+            r#"{"project": "PROJ", "summary": "S", "type": "Bug", "description": "Body"}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.issue_type, "Bug");
+        assert_eq!(issue.description, "Body");
+
+        assert!(serde_json::from_str::<NdjsonIssue>(r#"{"project": "PROJ"}"#).is_err());
+    }
+
+    #[test]
+    fn test_ndjson_update_line() {
+        let update: NdjsonUpdate =
+            serde_json::from_str(r#"{"key": "PROJ-1", "fields": {"summary": "New"}}"#).unwrap();
+        assert_eq!(update.key, "PROJ-1");
+        assert_eq!(update.fields["summary"], "New");
+
+        assert!(serde_json::from_str::<NdjsonUpdate>(r#"{"key": "PROJ-1"}"#).is_err());
+        assert!(
+            serde_json::from_str::<NdjsonUpdate>(r#"{"key": "PROJ-1", "fields": "x"}"#).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_from_ndjson_continues_past_invalid_lines() {
+        let mut config = create_test_config(vec![], None);
+        config.quiet = true;
+        let input = "{\"key\": \"PROJ-1\"}\n\nnot json\n";
+
+        let summary = update_from_ndjson(input.as_bytes(), &config).await.unwrap();
+        assert_eq!(summary, json!({"updated": 0, "failed": 2}));
+    }
+
+    #[test]
+    fn test_validate_jql_empty_query() {
+        let unfiltered = create_test_config(vec![], None);
+        let err = validate_jql(&apply_project_filter("  ", &unfiltered)).unwrap_err();
+        assert!(err.to_string().starts_with("JQL query is empty"));
+        assert!(validate_jql("ORDER BY created DESC").is_err());
+
+        // An empty query falls back to the configured projects
+        let filtered = create_test_config(vec!["PROJ1".to_string()], None);
+        let final_jql = apply_project_filter("", &filtered);
+        assert_eq!(final_jql, "project IN (\"PROJ1\")");
+        assert!(validate_jql(&final_jql).is_ok());
+        assert!(validate_jql(&apply_project_filter("ORDER BY created", &filtered)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_jql_syntax() {
+        let err = validate_jql("summary ~ \"unterminated").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid JQL: unterminated"));
+    }
+
+    #[test]
+    fn test_markdown_description_unexpected_types() {
+        let config = create_test_config(vec![], None);
+        let issue = |desc: Value| json!({"key": "PROJ-1", "fields": {"description": desc}});
+
+        let cases = [
+            (json!(42), json!("42")),
+            (json!(["a", 1]), json!("[\"a\",1]")),
+            (json!(true), json!("true")),
+            (json!("plain text"), json!("plain text")),
+            (Value::Null, Value::Null),
+        ];
+        for (desc, expected) in cases {
+            let simplified = simplify_issue(&issue(desc.clone()), true, false, &config);
+            assert_eq!(simplified["description"], expected);
+
+            let mut raw = issue(desc);
+            convert_issue_to_markdown(&mut raw, &config);
+            assert_eq!(raw["fields"]["description"], expected);
+        }
+
+        // Without markdown the description is passed through untouched
+        let simplified = simplify_issue(&issue(json!(42)), false, false, &config);
+        assert_eq!(simplified["description"], json!(42));
+    }
+
+    #[test]
+    fn test_full_users_keep_account_id() {
+        let config = create_test_config(vec![], None);
+        let data = json!({
+            "key": "PROJ-1",
+            "fields": {
+                "assignee": {"accountId": "5b10a2844c20165700ede21g", "displayName": "Alice", "active": true},
+                "reporter": null
+            }
+        });
+
+        let plain = simplify_issue(&data, false, false, &config);
+        assert_eq!(plain["assignee"], "Alice");
+
+        let full = simplify_issue(&data, false, true, &config);
+        assert_eq!(
+            full["assignee"],
+            json!({"accountId": "5b10a2844c20165700ede21g", "displayName": "Alice"})
+        );
+        assert_eq!(full["reporter"], Value::Null);
+
+        let dc_user = json!({"name": "alice", "key": "JIRAUSER1", "displayName": "Alice"});
+        assert_eq!(
+            extract_user_ref(&dc_user),
+            json!({"accountId": null, "displayName": "Alice", "name": "alice"})
+        );
+    }
+
+    #[test]
+    fn test_find_issue_type_by_name_or_id() {
+        let types = vec![
+            json!({"id": "10001", "name": "Task"}),
+            json!({"id": "10002", "name": "Bug"}),
+        ];
+        assert_eq!(find_issue_type(&types, "bug").unwrap()["id"], "10002");
+        assert_eq!(find_issue_type(&types, "10001").unwrap()["name"], "Task");
+        assert!(find_issue_type(&types, "Epic").is_none());
+    }
+
+    #[test]
+    fn test_simplify_create_field() {
+        let field = json!({
+            "fieldId": "priority",
+            "name": "Priority",
+            "required": true,
+            "schema": {"type": "priority", "system": "priority"},
+            "allowedValues": [{"id": "1", "name": "Highest"}, {"id": "3", "name": "Medium"}],
+            "hasDefaultValue": true
+        });
+        assert_eq!(
+            simplify_create_field(&field),
+            json!({
+                "key": "priority",
+                "name": "Priority",
+                "required": true,
+                "type": "priority",
+                "allowed_values": ["Highest", "Medium"],
+                "has_default": true
+            })
+        );
+
+        let labels = json!({
+            "fieldId": "customfield_10020",
+            "name": "Teams",
+            "required": false,
+            "schema": {"type": "array", "items": "option", "custom": "com.atlassian.jira.plugin.system.customfieldtypes:multiselect"},
+            "allowedValues": [{"id": "1", "value": "Core"}]
+        });
+        let simplified = simplify_create_field(&labels);
+        assert_eq!(simplified["type"], "array<option>");
+        assert_eq!(simplified["allowed_values"], json!(["Core"]));
+        assert_eq!(
+            simplified["custom"],
+            "com.atlassian.jira.plugin.system.customfieldtypes:multiselect"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_all_partial_ok_keeps_fetched_pages() {
+        // First page succeeds with a next token, the second fails
+        let (base_url, server) = serve(vec![
+            (
+                "200 OK",
+                r#"{"issues":[{"key":"PROJ-1"},{"key":"PROJ-2"}],"nextPageToken":"t2"}"#,
+            ),
+            ("500 Internal Server Error", r#"{"errorMessages":["boom"]}"#),
+        ]);
+
+        let mut config = create_test_config(vec![], None);
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+        config.quiet = true;
+
+        let result = search_all(
+            "project = PROJ",
+            None,
+            pagination::Sink::Collect,
+            pagination::Paging::default(),
+            false,
+            true,
+            &config,
+        )
+        .await
+        .unwrap();
+        server.join().unwrap();
+
+        assert_eq!(result["total"], 2);
+        assert_eq!(result["items"][1]["key"], "PROJ-2");
+        assert_eq!(result["complete"], false);
+        assert_eq!(result["errors"][0]["page"], 2);
+        assert_eq!(result["errors"][0]["fetched"], 2);
+        assert!(
+            result["errors"][0]["error"]
+                .as_str()
+                .unwrap()
+                .contains("500")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_all_stops_on_is_last_despite_token() {
+        // A single page; following its token would find the stub gone and fail
+        let (base_url, server) = serve(vec![(
+            "200 OK",
+            r#"{"issues":[{"key":"PROJ-1"}],"nextPageToken":"t2","isLast":true}"#,
+        )]);
+
+        let mut config = create_test_config(vec![], None);
+        use_stub(&mut config, &base_url);
+        config.performance.requests_per_second = 100.0;
+        config.quiet = true;
+
+        let result = search_all(
+            "project = PROJ",
+            None,
+            pagination::Sink::Collect,
+            pagination::Paging::default(),
+            false,
+            false,
+            &config,
+        )
+        .await
+        .unwrap();
+        server.join().unwrap();
+
+        assert_eq!(result["total"], 1);
+        assert_eq!(result["items"][0]["key"], "PROJ-1");
+    }
+
+    #[test]
+    fn test_find_transition_prefers_configured_order() {
+        let transitions = vec![
+            json!({"id": "11", "name": "Start", "to": {"name": "Doing"}}),
+            json!({"id": "21", "name": "Begin work", "to": {"name": "In Progress"}}),
+            json!({"id": "31", "name": "Done", "to": {"name": "Done"}}),
+        ];
+        let names = vec!["in progress".to_string(), "Start".to_string()];
+        assert_eq!(find_transition(&transitions, &names).unwrap()["id"], "21");
+
+        let names = vec!["Start".to_string()];
+        assert_eq!(find_transition(&transitions, &names).unwrap()["id"], "11");
+
+        let names = vec!["Review".to_string()];
+        assert!(find_transition(&transitions, &names).is_none());
+    }
+
+    #[test]
+    fn test_find_by_name_ignores_case() {
+        let items = json!([
+            {"id": "1", "name": "Backend"},
+            {"id": "2", "name": "v1.0"}
+        ]);
+        assert_eq!(find_by_name(&items, "backend").unwrap()["id"], "1");
+        assert_eq!(find_by_name(&items, " V1.0 ").unwrap()["id"], "2");
+        assert!(find_by_name(&items, "Frontend").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_component_returns_existing() {
+        let (base_url, server) = serve_once("200 OK", r#"[{"id":"10","name":"Backend"}]"#);
+
+        let mut config = create_test_config(vec![], None);
+        use_stub(&mut config, &base_url);
+
+        let result = create_component("PROJ", "backend", false, &config)
+            .await
+            .unwrap();
+        let request = server.join().unwrap();
+        assert!(request.line.starts_with("GET "));
+        assert_eq!(
+            result,
+            json!({"created": false, "component": {"id": "10", "name": "Backend"}})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_attachment_forbidden_message() {
+        let (base_url, server) = serve_once("403 Forbidden", r#"{"errorMessages":["no"]}"#);
+
+        let mut config = create_test_config(vec![], None);
+        use_stub(&mut config, &base_url);
+
+        let err = delete_attachment("10001", &config).await.unwrap_err();
+        let request = server.join().unwrap();
+        assert!(request.line.starts_with("DELETE "));
+        assert!(request.line.contains("/rest/api/3/attachment/10001"));
+        assert!(err.to_string().contains("403"));
+        assert!(err.to_string().contains("don't have permission"));
+    }
 }
@@ -18,17 +18,33 @@ pub const DEFAULT_SEARCH_FIELDS: &[&str] = &[
     "subtasks",
 ];
 
+/// Resolve the `fields` list for a search request.
+/// Entries prefixed with `-` (e.g. `-description,-customfield_10015`) are exclusions:
+/// they are removed from the explicit fields if any were given, otherwise from the default set.
 pub fn resolve_search_fields(
     api_fields: Option<Vec<String>>,
     include_description: bool,
     config: &crate::config::Config,
 ) -> Vec<String> {
-    if let Some(fields) = api_fields
-        && !fields.is_empty()
-    {
-        return fields;
+    let (excludes, includes): (Vec<String>, Vec<String>) = api_fields
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|f| f.starts_with('-'));
+
+    let mut fields = if includes.is_empty() {
+        default_search_fields(include_description, config)
+    } else {
+        includes
+    };
+
+    if !excludes.is_empty() {
+        fields.retain(|f| !excludes.iter().any(|e| &e[1..] == f));
     }
 
+    fields
+}
+
+fn default_search_fields(include_description: bool, config: &crate::config::Config) -> Vec<String> {
     if let Some(ref env_defaults) = config.jira.search_default_fields {
         let mut fields = env_defaults.clone();
         if include_description && !fields.iter().any(|f| f == "description") {
@@ -54,6 +70,58 @@ pub fn resolve_search_fields(
     fields
 }
 
+pub const GET_ISSUE_FIELDS: &[&str] = &[
+    "summary",
+    "description",
+    "status",
+    "priority",
+    "issuetype",
+    "assignee",
+    "reporter",
+    "project",
+    "created",
+    "updated",
+    "issuelinks",
+];
+
+/// `--fields all` (or Jira's own `*all`) asks for every field, unsimplified
+pub fn is_all_fields(api_fields: Option<&[String]>) -> bool {
+    matches!(api_fields, Some([f]) if f == "all" || f == "*all")
+}
+
+/// Resolve the `fields` list for `jira get`, using the same `-` exclusion syntax as search.
+/// Comments are fetched separately, so `comment` is not requested as a field; the returned
+/// flag says whether they were selected (always, unless explicit fields omit them).
+pub fn resolve_get_fields(
+    api_fields: Option<Vec<String>>,
+    include_attachments: bool,
+    include_comments: bool,
+) -> (Vec<String>, bool) {
+    let (excludes, includes): (Vec<String>, Vec<String>) = api_fields
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|f| f.starts_with('-'));
+    let is_comment = |f: &str| f == "comment" || f == "comments";
+
+    let (mut fields, mut comments) = if includes.is_empty() {
+        let mut fields: Vec<String> = GET_ISSUE_FIELDS.iter().map(|s| s.to_string()).collect();
+        if include_attachments {
+            fields.push("attachment".to_string());
+        }
+        (fields, include_comments)
+    } else {
+        let comments = includes.iter().any(|f| is_comment(f));
+        (includes, comments)
+    };
+
+    fields.retain(|f| !is_comment(f) && !excludes.iter().any(|e| &e[1..] == f));
+    if excludes.iter().any(|e| is_comment(&e[1..])) {
+        comments = false;
+    }
+
+    (fields, comments)
+}
+
 pub const ESSENTIAL_FIELDS: &[&str] = &[
     "key",
     "summary",
@@ -86,6 +154,56 @@ mod tests {
     use super::*;
     use crate::test_utils::create_test_config_with_fields;
 
+    #[test]
+    fn test_resolve_get_fields_default() {
+        let (fields, comments) = resolve_get_fields(None, true, true);
+        assert_eq!(fields.len(), GET_ISSUE_FIELDS.len() + 1);
+        assert!(fields.contains(&"attachment".to_string()));
+        assert!(comments);
+
+        let (fields, comments) = resolve_get_fields(None, false, false);
+        assert!(!fields.contains(&"attachment".to_string()));
+        assert!(!comments);
+    }
+
+    #[test]
+    fn test_resolve_get_fields_explicit_skips_comments() {
+        let (fields, comments) = resolve_get_fields(Some(vec!["status".to_string()]), true, true);
+        assert_eq!(fields, vec!["status"]);
+        assert!(!comments);
+
+        let (fields, comments) = resolve_get_fields(
+            Some(vec!["status".to_string(), "comment".to_string()]),
+            true,
+            true,
+        );
+        assert_eq!(fields, vec!["status"]);
+        assert!(comments);
+    }
+
+    #[test]
+    fn test_is_all_fields() {
+        assert!(is_all_fields(Some(&["all".to_string()])));
+        assert!(is_all_fields(Some(&["*all".to_string()])));
+        assert!(!is_all_fields(Some(&[
+            "all".to_string(),
+            "status".to_string()
+        ])));
+        assert!(!is_all_fields(None));
+    }
+
+    #[test]
+    fn test_resolve_get_fields_exclusions() {
+        let (fields, comments) = resolve_get_fields(
+            Some(vec!["-description".to_string(), "-comment".to_string()]),
+            true,
+            true,
+        );
+        assert!(!fields.contains(&"description".to_string()));
+        assert!(fields.contains(&"summary".to_string()));
+        assert!(!comments);
+    }
+
     #[test]
     fn test_default_search_fields_count() {
         assert_eq!(DEFAULT_SEARCH_FIELDS.len(), 17);
@@ -150,6 +268,64 @@ mod tests {
         assert_eq!(result, vec!["key"]);
     }
 
+    #[test]
+    fn test_resolve_exclusion_from_defaults() {
+        let config = create_test_config_with_fields(None, vec!["customfield_10015".to_string()]);
+        let api_fields = Some(vec![
+            "-customfield_10015".to_string(),
+            "-subtasks".to_string(),
+        ]);
+        let result = resolve_search_fields(api_fields, true, &config);
+        assert_eq!(result.len(), 17);
+        assert!(result.contains(&"description".to_string()));
+        assert!(!result.contains(&"customfield_10015".to_string()));
+        assert!(!result.contains(&"subtasks".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_exclusion_from_explicit_fields() {
+        let config = create_test_config_with_fields(None, vec![]);
+        let api_fields = Some(vec![
+            "key".to_string(),
+            "summary".to_string(),
+            "-summary".to_string(),
+        ]);
+        let result = resolve_search_fields(api_fields, false, &config);
+        assert_eq!(result, vec!["key"]);
+    }
+
+    #[test]
+    fn test_resolve_exclusion_from_env_defaults() {
+        let config = create_test_config_with_fields(
+            Some(vec!["key".to_string(), "summary".to_string()]),
+            vec![],
+        );
+        let result = resolve_search_fields(Some(vec!["-summary".to_string()]), true, &config);
+        assert_eq!(result, vec!["key", "description"]);
+    }
+
+    #[test]
+    fn test_get_issue_fields() {
+        assert_eq!(
+            GET_ISSUE_FIELDS,
+            [
+                "summary",
+                "description",
+                "status",
+                "priority",
+                "issuetype",
+                "assignee",
+                "reporter",
+                "project",
+                "created",
+                "updated",
+                "issuelinks",
+            ]
+        );
+        assert!(!GET_ISSUE_FIELDS.contains(&"attachment"));
+        assert!(!GET_ISSUE_FIELDS.contains(&"comment"));
+    }
+
     #[test]
     fn test_essential_fields() {
         assert_eq!(ESSENTIAL_FIELDS.len(), 11);
@@ -1,11 +1,16 @@
+pub mod attachment;
 pub mod config;
 pub mod confluence;
+pub mod error;
 pub mod filter;
 pub mod http;
 pub mod jira;
 pub mod markdown;
+pub mod output;
+pub mod pagination;
+pub mod util;
 
 #[cfg(test)]
 pub mod test_utils;
 
-pub use config::Config;
+pub use config::{AuthType, Config, Deployment};
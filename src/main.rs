@@ -1,12 +1,48 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use atlassian_cli::error::AppError;
+use atlassian_cli::pagination::{Export, Paging, Sink};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Copy, Default, ValueEnum)]
 pub enum OutputFormat {
     #[default]
     Html,
     Markdown,
+    /// Plain text with all markup stripped (confluence get only)
+    Text,
+}
+
+impl OutputFormat {
+    fn as_markdown(self) -> Result<bool> {
+        match self {
+            OutputFormat::Text => {
+                anyhow::bail!("--format text is only supported by confluence get")
+            }
+            format => Ok(matches!(format, OutputFormat::Markdown)),
+        }
+    }
+}
+
+/// Encoding for command results on stdout
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum OutputEncoding {
+    #[default]
+    Json,
+    Yaml,
+    /// Aligned columns for reading in a terminal (status/priority colored on a TTY)
+    Table,
+}
+
+impl From<atlassian_cli::config::OutputEncoding> for OutputEncoding {
+    fn from(encoding: atlassian_cli::config::OutputEncoding) -> Self {
+        use atlassian_cli::config::OutputEncoding as Configured;
+        match encoding {
+            Configured::Json => Self::Json,
+            Configured::Yaml => Self::Yaml,
+            Configured::Table => Self::Table,
+        }
+    }
 }
 
 /// Parse string input that may be either plain text or ADF JSON.
@@ -28,7 +64,7 @@ struct Cli {
     #[arg(long, help = "Config file path")]
     config: Option<PathBuf>,
 
-    #[arg(long, help = "Profile name")]
+    #[arg(long, help = "Profile name (defaults to $ATLASSIAN_PROFILE)")]
     profile: Option<String>,
 
     #[arg(long, env = "ATLASSIAN_DOMAIN")]
@@ -40,11 +76,71 @@ struct Cli {
     #[arg(long, env = "ATLASSIAN_API_TOKEN")]
     token: Option<String>,
 
-    #[arg(long, help = "Pretty-print JSON output")]
+    #[arg(long, overrides_with = "no_pretty", help = "Pretty-print JSON output")]
     pretty: bool,
 
+    #[arg(
+        long,
+        overrides_with = "pretty",
+        help = "Compact JSON even if the profile sets output.pretty"
+    )]
+    no_pretty: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Output encoding [default: json, or the profile's output.format]"
+    )]
+    output: Option<OutputEncoding>,
+
+    #[arg(long, help = "Disable colors in table output (also honors NO_COLOR)")]
+    no_color: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Shift markdown headings converted from ADF down N levels (capped at H6)"
+    )]
+    heading_offset: Option<u8>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Write every request and response (credentials redacted) to files in DIR"
+    )]
+    dump: Option<PathBuf>,
+
     #[arg(short, long, action = clap::ArgAction::Count, help = "Verbose logging")]
     verbose: u8,
+
+    #[arg(short, long, help = "Suppress progress output (overridden by -v)")]
+    quiet: bool,
+
+    #[arg(
+        long,
+        help = "Report pagination progress as JSON lines on stderr ({\"page\", \"fetched\", \"total\"})"
+    )]
+    progress_json: bool,
+
+    #[arg(
+        long,
+        help = "Skip TLS certificate verification (self-signed test instances only)"
+    )]
+    insecure: bool,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        value_parser = clap::value_parser!(u64).range(100..=60000),
+        help = "Request timeout in milliseconds, overriding performance.request_timeout_ms (100-60000)"
+    )]
+    timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "On failure, print {\"error\": {kind, status, message}} to stdout and exit with a per-kind code"
+    )]
+    errors_json: bool,
 }
 
 #[derive(Subcommand)]
@@ -67,20 +163,184 @@ enum JiraSubcommand {
         issue_key: String,
         #[arg(long, value_enum, default_value = "html", help = "ADF content format")]
         format: OutputFormat,
+        #[arg(
+            long,
+            overrides_with = "no_comments",
+            help = "Include comments (default)"
+        )]
+        comments: bool,
+        #[arg(long, overrides_with = "comments", help = "Skip fetching comments")]
+        no_comments: bool,
+        #[arg(
+            long,
+            overrides_with = "no_attachments",
+            help = "Include attachments (default)"
+        )]
+        attachments: bool,
+        #[arg(
+            long,
+            overrides_with = "attachments",
+            help = "Skip fetching attachments"
+        )]
+        no_attachments: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            allow_hyphen_values = true,
+            help = "Fields to return (prefix with - to exclude; add comment for comments; all for the raw issue with every field)"
+        )]
+        fields: Option<Vec<String>>,
+        #[arg(long, help = "Return assignee/reporter as {accountId, displayName}")]
+        full_users: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Expand extra data (e.g., changelog,renderedFields,transitions)"
+        )]
+        expand: Option<Vec<String>>,
+        #[arg(
+            long,
+            help = "Return the issue as Jira sends it, without simplification"
+        )]
+        raw: bool,
+    },
+    /// Get several issues in one search (keys as arguments, or - / none for stdin)
+    #[command(name = "get-many")]
+    GetMany {
+        keys: Vec<String>,
+        #[arg(long, value_enum, default_value = "html", help = "ADF content format")]
+        format: OutputFormat,
+        #[arg(long, help = "Also fetch comments (one request per issue)")]
+        comments: bool,
+        #[arg(long, help = "Include attachment metadata")]
+        attachments: bool,
+        #[arg(long, help = "Return assignee/reporter as {accountId, displayName}")]
+        full_users: bool,
     },
     #[command(alias = "list", alias = "ls", alias = "find", alias = "jql")]
     Search {
-        jql: String,
-        #[arg(long, default_value = "100", help = "Results per page")]
-        limit: u32,
+        #[arg(help = "JQL query, or - to read it from stdin")]
+        jql: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "jql",
+            help = "Read the JQL query from a file (- for stdin)"
+        )]
+        jql_file: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "Results per page [default: 100, or the profile's output.default_limit]"
+        )]
+        limit: Option<u32>,
         #[arg(long, help = "Fetch all results via token pagination")]
         all: bool,
         #[arg(long, help = "Stream as JSONL (requires --all)")]
         stream: bool,
-        #[arg(long, value_delimiter = ',', help = "Fields to return")]
+        #[arg(long, help = "Stop after N results (requires --all)")]
+        max: Option<usize>,
+        #[arg(
+            long,
+            requires = "all",
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Issues per request with --all (max 100) [default: 100]"
+        )]
+        page_size: Option<u32>,
+        #[arg(
+            long,
+            help = "On a failed page, return the issues fetched so far with an errors array (requires --all)"
+        )]
+        partial_ok: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "all",
+            conflicts_with_all = ["stream", "group_by"],
+            help = "Write results to a file as pages arrive instead of stdout (requires --all)"
+        )]
+        export: Option<PathBuf>,
+        #[arg(
+            long,
+            requires = "export",
+            help = "Export as NDJSON (one issue per line) instead of a JSON array"
+        )]
+        jsonl: bool,
+        #[arg(
+            long,
+            conflicts_with = "all",
+            help = "Continue from the nextPageToken of a previous search"
+        )]
+        page_token: Option<String>,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            allow_hyphen_values = true,
+            help = "Fields to return (prefix with - to exclude, e.g. -description)"
+        )]
         fields: Option<Vec<String>>,
+        #[arg(
+            long,
+            help = "Only issues created within a duration (e.g. 7d, 24h, 2w)"
+        )]
+        since: Option<String>,
+        #[arg(
+            long,
+            help = "Only issues updated within a duration (e.g. 7d, 24h, 2w)"
+        )]
+        updated_since: Option<String>,
+        #[arg(
+            long,
+            value_name = "FIELD:DIR",
+            help = "Sort by a field, replacing any ORDER BY in the JQL (repeatable, e.g. updated:desc)"
+        )]
+        order: Vec<String>,
+        #[arg(long, conflicts_with_all = ["all", "stream"], help = "Return only the match count")]
+        count_only: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["stream", "count_only"],
+            help = "Return issue counts grouped by status, assignee, type or priority"
+        )]
+        group_by: Option<atlassian_cli::jira::GroupBy>,
+        #[arg(
+            long,
+            help = "Exit with code 2 when nothing matches (the empty result is still printed)"
+        )]
+        fail_on_empty: bool,
         #[arg(long, value_enum, default_value = "html", help = "ADF content format")]
         format: OutputFormat,
+        #[arg(
+            long,
+            conflicts_with_all = ["count_only", "group_by"],
+            help = "Return issues as Jira sends them, without response filtering"
+        )]
+        raw: bool,
+    },
+    /// Poll a JQL and print newly matching issues as JSONL until Ctrl-C
+    Watch {
+        #[arg(help = "JQL query, or - to read it from stdin")]
+        jql: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "jql",
+            help = "Read the JQL query from a file (- for stdin)"
+        )]
+        jql_file: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value = "60",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Seconds between polls"
+        )]
+        interval: u64,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            allow_hyphen_values = true,
+            help = "Fields to return (prefix with - to exclude, e.g. -description)"
+        )]
+        fields: Option<Vec<String>>,
     },
     Create {
         project: Option<String>,
@@ -94,10 +354,46 @@ enum JiraSubcommand {
         type_flag: Option<String>,
         #[arg(long)]
         description: Option<String>,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Start from [jira.templates.NAME]; explicit arguments override it"
+        )]
+        template: Option<String>,
+        #[arg(
+            long,
+            num_args = 0..=1,
+            default_missing_value = "10",
+            value_name = "MINUTES",
+            help = "Return an existing issue with the same project and summary you created within MINUTES (default 10) instead of creating a duplicate"
+        )]
+        dedupe: Option<u64>,
+        #[arg(
+            long,
+            conflicts_with_all = ["project", "summary", "issue_type", "project_flag", "summary_flag", "type_flag", "description", "template"],
+            help = "Create one issue per NDJSON line from stdin ({\"project\", \"summary\", \"issue_type\", \"description\"}), printing one result line each"
+        )]
+        from_stdin: bool,
     },
-    Update {
+    Update(UpdateArgs),
+    /// Update many issues from NDJSON lines of {"key", "fields"}, printing one result line each
+    #[command(name = "bulk-update")]
+    BulkUpdate {
+        #[arg(
+            long,
+            required = true,
+            help = "Read NDJSON lines ({\"key\": \"PROJ-1\", \"fields\": {...}}) from stdin"
+        )]
+        from_stdin: bool,
+    },
+    /// Add or remove labels without replacing the existing set
+    #[command(alias = "labels")]
+    Label {
         issue_key: String,
-        fields: String,
+        #[arg(long, help = "Label to add (repeatable)")]
+        add: Vec<String>,
+        #[arg(long, help = "Label to remove (repeatable)")]
+        remove: Vec<String>,
     },
     /// List comments for an issue
     #[command(alias = "comment")]
@@ -106,6 +402,14 @@ enum JiraSubcommand {
         #[arg(long, value_enum, default_value = "html", help = "ADF content format")]
         format: OutputFormat,
     },
+    /// Get a single comment
+    #[command(name = "comment-get")]
+    CommentGet {
+        issue_key: String,
+        comment_id: String,
+        #[arg(long, value_enum, default_value = "html", help = "ADF content format")]
+        format: OutputFormat,
+    },
     /// Add a comment to an issue
     #[command(name = "comment-add")]
     CommentAdd {
@@ -126,9 +430,38 @@ enum JiraSubcommand {
         #[arg(long = "transition-id", alias = "to")]
         transition_id_flag: Option<String>,
     },
+    /// Assign an issue to yourself
+    #[command(alias = "assign-me")]
+    Take {
+        issue_key: String,
+    },
+    /// Move an issue into progress using the [jira.workflow] start transition names
+    Start {
+        issue_key: String,
+    },
     #[command(alias = "statuses")]
     Transitions {
         issue_key: String,
+        #[arg(
+            long,
+            value_name = "fields",
+            value_parser = ["fields"],
+            help = "Expand transition screens and list required fields per transition"
+        )]
+        expand: Option<String>,
+    },
+    /// Show the fields needed to create an issue of a type in a project
+    #[command(name = "create-meta")]
+    CreateMeta {
+        project: String,
+        #[arg(help = "Issue type name or ID")]
+        issue_type: String,
+    },
+    /// Show the change history of an issue
+    Changelog {
+        issue_key: String,
+        #[arg(long, help = "Only changes to this field (e.g. status)")]
+        field: Option<String>,
     },
     /// List attachments for an issue
     Attachments {
@@ -138,15 +471,98 @@ enum JiraSubcommand {
     #[command(name = "attachment-download")]
     AttachmentDownload {
         attachment_id: String,
-        #[arg(long, short, help = "Output file path")]
+        #[arg(
+            long,
+            short,
+            help = "Output file path, or an existing directory to save it under its own name"
+        )]
         output: Option<PathBuf>,
+        #[arg(long, help = "Replace the output file if it already exists")]
+        overwrite: bool,
     },
+    /// Delete an attachment by ID
+    #[command(name = "attachment-delete")]
+    AttachmentDelete {
+        attachment_id: String,
+        #[arg(long, short, help = "Delete without asking")]
+        yes: bool,
+    },
+    /// Attach a file to an issue
+    #[command(name = "attachment-upload")]
+    AttachmentUpload {
+        issue_key: String,
+        #[arg(help = "File to upload")]
+        file: PathBuf,
+    },
+    /// List sprints of a board
+    Sprints {
+        board_id: u64,
+        #[arg(
+            long,
+            help = "Filter by state: active, future, closed (comma-separated)"
+        )]
+        state: Option<String>,
+    },
+    /// List issues in a sprint
+    #[command(name = "sprint-issues")]
+    SprintIssues {
+        sprint_id: u64,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            allow_hyphen_values = true,
+            help = "Fields to return (prefix with - to exclude, e.g. -description)"
+        )]
+        fields: Option<Vec<String>>,
+    },
+    /// Move issues into a sprint (keys as arguments, or - / none for stdin)
+    #[command(name = "move-to-sprint")]
+    MoveToSprint {
+        sprint_id: u64,
+        keys: Vec<String>,
+    },
+    /// List projects visible to you (limited to projects_filter when set)
+    Projects,
+    /// Show one project with its lead, issue types, components and versions
+    Project {
+        key: String,
+    },
+    /// List components of a project
+    Components {
+        project: String,
+    },
+    /// Create a component, or return the existing one with the same name
+    #[command(name = "component-create")]
+    ComponentCreate {
+        project: String,
+        name: String,
+        #[arg(long, help = "Fail if the component already exists")]
+        strict: bool,
+    },
+    /// List versions of a project
+    #[command(alias = "releases")]
+    Versions {
+        project: String,
+    },
+    /// Create a version, or return the existing one with the same name
+    #[command(name = "version-create")]
+    VersionCreate {
+        project: String,
+        name: String,
+        #[arg(long, help = "Fail if the version already exists")]
+        strict: bool,
+    },
+    /// Show the authenticated user (accountId, timezone)
+    #[command(alias = "me", alias = "whoami")]
+    Myself,
     /// Search for users by name or email
     #[command(name = "user-search")]
     UserSearch {
         query: String,
         #[arg(long, default_value = "50", help = "Max results to return")]
         limit: u32,
+        #[arg(long, help = "Fetch all matches (--limit becomes the page size)")]
+        all: bool,
     },
     /// List favourite/saved filters
     #[command(alias = "filter")]
@@ -155,19 +571,38 @@ enum JiraSubcommand {
     #[command(name = "filter-run")]
     FilterRun {
         filter: String,
-        #[arg(long, default_value = "100")]
-        limit: u32,
+        #[arg(
+            long,
+            help = "Results per page [default: 100, or the profile's output.default_limit]"
+        )]
+        limit: Option<u32>,
         #[arg(long)]
         all: bool,
         #[arg(long)]
         stream: bool,
-        #[arg(long, value_delimiter = ',')]
+        #[arg(long)]
+        max: Option<usize>,
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
         fields: Option<Vec<String>>,
         #[arg(long, value_enum, default_value = "html")]
         format: OutputFormat,
     },
 }
 
+#[derive(Args)]
+struct UpdateArgs {
+    issue_key: String,
+    #[arg(help = "Fields JSON")]
+    fields: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "fields",
+        help = "Read the fields JSON from a file (- for stdin)"
+    )]
+    from_file: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 struct ConfluenceCommand {
     #[command(subcommand)]
@@ -177,49 +612,165 @@ struct ConfluenceCommand {
 #[derive(Subcommand)]
 enum ConfluenceSubcommand {
     Search {
-        query: String,
+        #[arg(help = "CQL query, or - to read it from stdin")]
+        query: Option<String>,
         #[arg(
             long,
-            default_value = "10",
-            help = "Results per page (max 250). With --all, controls batch size"
+            value_name = "PATH",
+            conflicts_with = "query",
+            help = "Read the CQL query from a file (- for stdin)"
         )]
-        limit: u32,
+        cql_file: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "Results per page (max 250; with --all see --page-size) [default: 10, or the profile's output.default_limit]"
+        )]
+        limit: Option<u32>,
         #[arg(long, help = "Fetch all results via cursor pagination")]
         all: bool,
         #[arg(long, help = "Stream as JSONL (requires --all)")]
         stream: bool,
+        #[arg(long, help = "Stop after N results (requires --all)")]
+        max: Option<usize>,
+        #[arg(
+            long,
+            requires = "all",
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Results per request with --all (max 50, as bodies are expanded) [default: 50]"
+        )]
+        page_size: Option<u32>,
+        #[arg(
+            long,
+            conflicts_with = "all",
+            help = "Continue from the cursor of a previous search (same query)"
+        )]
+        cursor: Option<String>,
+        #[arg(long, conflicts_with_all = ["all", "stream"], help = "Return only the match count")]
+        count_only: bool,
+        #[arg(
+            long = "type",
+            value_parser = ["page", "blogpost", "comment", "attachment"],
+            help = "Only this content type (adds type = ... unless the CQL already has a type clause)"
+        )]
+        content_type: Option<String>,
         #[arg(
             long,
             value_delimiter = ',',
             help = "Expand fields (e.g., body.storage,ancestors)"
         )]
         expand: Option<Vec<String>>,
+        #[arg(
+            long,
+            help = "Exit with code 2 when nothing matches (the empty result is still printed)"
+        )]
+        fail_on_empty: bool,
         #[arg(long, value_enum, default_value = "html", help = "Body content format")]
         format: OutputFormat,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            conflicts_with_all = ["stream", "count_only"],
+            help = "Keep only these top-level keys of each result (e.g. id,title,space)"
+        )]
+        fields: Option<Vec<String>>,
     },
     Get {
         page_id: String,
         #[arg(long, value_enum, default_value = "html", help = "Body content format")]
         format: OutputFormat,
+        #[arg(long, help = "Fetch a specific historical version")]
+        version: Option<u32>,
+    },
+    /// Get a page by its exact title within a space
+    #[command(name = "get-by-title")]
+    GetByTitle {
+        space: String,
+        title: String,
+        #[arg(long, value_enum, default_value = "html", help = "Body content format")]
+        format: OutputFormat,
     },
     Create {
         space: String,
         title: String,
         content: String,
+        #[arg(
+            long,
+            value_name = "TITLE",
+            help = "Create under the page with this exact title in the same space"
+        )]
+        parent_title: Option<String>,
     },
+    /// Update a page; an omitted title or content keeps the current one
+    #[command(group = clap::ArgGroup::new("changes").required(true).multiple(true))]
     Update {
         page_id: String,
-        title: String,
-        content: String,
+        #[arg(long, group = "changes", help = "New title (default: keep current)")]
+        title: Option<String>,
+        #[arg(
+            long,
+            group = "changes",
+            help = "New storage-format body (default: keep current)"
+        )]
+        content: Option<String>,
     },
     Children {
         page_id: String,
+        #[arg(
+            long,
+            visible_alias = "all",
+            help = "Walk all descendants depth-first and return a nested tree"
+        )]
+        recursive: bool,
+        #[arg(
+            long,
+            requires = "recursive",
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Maximum depth below the page when using --recursive"
+        )]
+        max_depth: Option<u32>,
     },
     Comments {
         page_id: String,
+        #[arg(long, help = "Inline comments instead of footer comments")]
+        inline: bool,
+        #[arg(long, help = "Fetch every comment, not just the first page")]
+        all: bool,
         #[arg(long, value_enum, default_value = "html", help = "Body content format")]
         format: OutputFormat,
     },
+    /// List a page's version history (number, author, date, message)
+    Versions { page_id: String },
+    /// List or download page attachments (`attachments <PAGE_ID>` lists)
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+    Attachments {
+        page_id: Option<String>,
+        #[command(subcommand)]
+        action: Option<AttachmentsSubcommand>,
+    },
+    /// Download an attachment by ID (same as `attachments download`)
+    #[command(name = "attachment-download", hide = true)]
+    AttachmentDownload(AttachmentDownloadArgs),
+}
+
+#[derive(Subcommand)]
+enum AttachmentsSubcommand {
+    /// List attachments for a page
+    List { page_id: String },
+    /// Download an attachment by ID
+    Download(AttachmentDownloadArgs),
+}
+
+#[derive(Args)]
+struct AttachmentDownloadArgs {
+    attachment_id: String,
+    #[arg(
+        long,
+        short,
+        help = "Output file path, or an existing directory to save it under its own name"
+    )]
+    output: Option<PathBuf>,
+    #[arg(long, help = "Replace the output file if it already exists")]
+    overwrite: bool,
 }
 
 #[derive(Parser)]
@@ -234,7 +785,11 @@ enum ConfigSubcommand {
         #[arg(long)]
         global: bool,
     },
-    Show,
+    /// Show the resolved config (token masked)
+    Show {
+        #[arg(long, help = "Print as JSON")]
+        json: bool,
+    },
     List,
     Edit {
         #[arg(long)]
@@ -244,7 +799,27 @@ enum ConfigSubcommand {
         #[arg(long)]
         global: bool,
     },
+    /// Move plaintext tokens out of the config file
+    Migrate {
+        #[arg(long)]
+        global: bool,
+        #[arg(long, short, help = "Rewrite the file without asking")]
+        yes: bool,
+    },
     Validate,
+    /// Check config files, permissions, credential sources, connectivity and auth
+    Doctor,
+    /// Print the effective config as shareable TOML (email and token omitted)
+    Export,
+    /// Merge a shared TOML file into your config, keeping your credentials
+    Import {
+        #[arg(help = "TOML file from `config export`")]
+        file: PathBuf,
+        #[arg(long)]
+        global: bool,
+        #[arg(long, short, help = "Write the file without asking")]
+        yes: bool,
+    },
 }
 
 #[tokio::main]
@@ -257,44 +832,186 @@ async fn main() -> Result<()> {
         2 => "debug",
         _ => "trace",
     };
+    // At trace, keep HTTP internals at debug so request headers never reach the logs
+    let log_filter = if cli.verbose >= 3 {
+        format!("{},hyper=debug,hyper_util=debug,h2=debug", log_level)
+    } else {
+        log_level.to_string()
+    };
 
     tracing_subscriber::fmt()
-        .with_env_filter(log_level)
+        .with_env_filter(log_filter)
         .with_writer(std::io::stderr)
         .init();
 
+    let errors_json = cli.errors_json;
+    let result = run(cli).await;
+    if errors_json && let Err(e) = &result {
+        let app = AppError::classify(e);
+        println!("{}", app.to_json());
+        std::process::exit(app.exit_code());
+    }
+    result
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    if let Some(dir) = &cli.dump {
+        atlassian_cli::http::enable_dump(dir)?;
+    }
     match cli.command {
-        Command::Config(cmd) => handle_config(cmd).await,
+        Command::Config(cmd) => {
+            handle_config(
+                cmd,
+                cli.config.as_ref(),
+                cli.profile.as_ref(),
+                cli.domain,
+                cli.email,
+                cli.token,
+            )
+            .await
+        }
         Command::Jira(cmd) => {
-            let config = atlassian_cli::Config::load(
+            let mut config = atlassian_cli::Config::load(
                 cli.config.as_ref(),
                 cli.profile.as_ref(),
                 cli.domain,
                 cli.email,
                 cli.token,
-            )?;
+            )
+            .map_err(config_error)?;
+            config.quiet = cli.quiet && cli.verbose == 0;
+            config.progress_json = cli.progress_json;
+            if cli.heading_offset.is_some() {
+                config.markdown.heading_offset = cli.heading_offset;
+            }
+            if let Some(timeout) = cli.timeout {
+                config.performance.request_timeout_ms = timeout;
+            }
+            apply_insecure(&mut config, cli.insecure);
+            config.raw = matches!(
+                cmd.subcommand,
+                JiraSubcommand::Get { raw: true, .. } | JiraSubcommand::Search { raw: true, .. }
+            );
 
-            let result = handle_jira(cmd, &config).await?;
-            output_json(&result, cli.pretty);
+            let fail_on_empty = matches!(
+                cmd.subcommand,
+                JiraSubcommand::Search {
+                    fail_on_empty: true,
+                    ..
+                }
+            );
+            let exporting = matches!(
+                cmd.subcommand,
+                JiraSubcommand::Search {
+                    export: Some(_),
+                    ..
+                }
+            );
+            let result = handle_jira(cmd, &config)
+                .await
+                .map_err(|e| atlassian_cli::util::redact_error(e, &config))?;
+            if exporting {
+                // The data went to the file; stdout stays empty
+                eprintln!("{}", serde_json::to_string(&result)?);
+                exit_if_empty(fail_on_empty, &result);
+                return Ok(());
+            }
+            let (output, pretty) = output_options(cli.output, cli.pretty, cli.no_pretty, &config);
+            output_result(&result, output, pretty, cli.no_color)?;
+            exit_if_empty(fail_on_empty, &result);
             Ok(())
         }
         Command::Confluence(cmd) => {
-            let config = atlassian_cli::Config::load(
+            let mut config = atlassian_cli::Config::load(
                 cli.config.as_ref(),
                 cli.profile.as_ref(),
                 cli.domain,
                 cli.email,
                 cli.token,
-            )?;
+            )
+            .map_err(config_error)?;
+            config.quiet = cli.quiet && cli.verbose == 0;
+            config.progress_json = cli.progress_json;
+            if cli.heading_offset.is_some() {
+                config.markdown.heading_offset = cli.heading_offset;
+            }
+            if let Some(timeout) = cli.timeout {
+                config.performance.request_timeout_ms = timeout;
+            }
+            apply_insecure(&mut config, cli.insecure);
 
-            let result = handle_confluence(cmd, &config).await?;
-            output_json(&result, cli.pretty);
+            let fail_on_empty = matches!(
+                cmd.subcommand,
+                ConfluenceSubcommand::Search {
+                    fail_on_empty: true,
+                    ..
+                }
+            );
+            let result = handle_confluence(cmd, &config)
+                .await
+                .map_err(|e| atlassian_cli::util::redact_error(e, &config))?;
+            let (output, pretty) = output_options(cli.output, cli.pretty, cli.no_pretty, &config);
+            output_result(&result, output, pretty, cli.no_color)?;
+            exit_if_empty(fail_on_empty, &result);
             Ok(())
         }
     }
 }
 
-async fn handle_config(cmd: ConfigCommand) -> Result<()> {
+/// Output flags, falling back to the profile's `[output]` defaults
+fn output_options(
+    output: Option<OutputEncoding>,
+    pretty: bool,
+    no_pretty: bool,
+    config: &atlassian_cli::Config,
+) -> (OutputEncoding, bool) {
+    let output = output
+        .or_else(|| config.output.format.map(Into::into))
+        .unwrap_or_default();
+    let pretty = pretty || (!no_pretty && config.output.pretty.unwrap_or(false));
+    (output, pretty)
+}
+
+/// `--fail-on-empty`: exit 2 after printing a search result with no matches
+fn exit_if_empty(fail_on_empty: bool, result: &serde_json::Value) {
+    let matched = result["count"]
+        .as_u64()
+        .or_else(|| result["total"].as_u64());
+    if fail_on_empty && matched == Some(0) {
+        use std::io::Write;
+        let _ = std::io::stdout().flush();
+        std::process::exit(2);
+    }
+}
+
+fn apply_insecure(config: &mut atlassian_cli::Config, insecure: bool) {
+    if insecure {
+        config.performance.accept_invalid_certs = true;
+    }
+    // Always shown, even with --quiet
+    if config.performance.accept_invalid_certs {
+        eprintln!(
+            "WARNING: TLS certificate verification is DISABLED (--insecure / accept_invalid_certs). \
+             Never use this against production instances."
+        );
+    }
+}
+
+fn config_error(error: anyhow::Error) -> anyhow::Error {
+    match AppError::classify(&error) {
+        AppError::Io(_) => error,
+        _ => AppError::Config(format!("{:#}", error)).into(),
+    }
+}
+
+async fn handle_config(
+    cmd: ConfigCommand,
+    config_path: Option<&PathBuf>,
+    profile: Option<&String>,
+    domain: Option<String>,
+    email: Option<String>,
+    token: Option<String>,
+) -> Result<()> {
     match cmd.subcommand {
         ConfigSubcommand::Init { global } => {
             let path = atlassian_cli::Config::init_config(global)?;
@@ -302,39 +1019,33 @@ async fn handle_config(cmd: ConfigCommand) -> Result<()> {
             println!("Edit it and add your credentials.");
             Ok(())
         }
-        ConfigSubcommand::Show => {
-            let config =
-                atlassian_cli::Config::load_without_validation(None, None, None, None, None)?;
+        ConfigSubcommand::Show { json } => {
+            let config = atlassian_cli::Config::load_without_validation(
+                config_path,
+                profile,
+                domain,
+                email,
+                token,
+            )?;
+            let effective = config.effective();
 
-            // Display credentials (not in Config struct's TOML serialization)
-            println!("[default]");
-            if let Some(ref domain) = config.domain {
-                println!("domain = {:?}", domain);
-            } else {
-                println!("# domain = (not set)");
-            }
-            if let Some(ref email) = config.email {
-                println!("email = {:?}", email);
-            } else {
-                println!("# email = (not set)");
+            if json {
+                println!("{}", serde_json::to_string_pretty(&effective)?);
+                return Ok(());
             }
-            if let Some(ref token) = config.token {
-                let mask_len = 4.min(token.len());
-                println!("token = \"{}***\"", &token[..mask_len]);
-            } else {
-                println!("# token = (not set)");
-            }
-            println!();
 
-            // Display rest of config via TOML serialization
-            let toml_str = toml::to_string_pretty(&config)?;
-            // Skip the empty [default] section if present
-            for line in toml_str.lines() {
-                if line.trim().is_empty() || line.trim() == "[default]" {
-                    continue;
+            // TOML has no null, so unset values are listed as comments
+            let mut profile = effective;
+            if let Some(fields) = profile.as_object_mut() {
+                for (key, value) in fields.iter() {
+                    if value.is_null() {
+                        println!("# {} = (not set)", key);
+                    }
                 }
-                println!("{}", line);
             }
+            strip_nulls(&mut profile);
+            let root = serde_json::json!({ "default": profile });
+            print!("{}", toml::to_string_pretty(&root)?);
             Ok(())
         }
         ConfigSubcommand::List => {
@@ -353,8 +1064,13 @@ async fn handle_config(cmd: ConfigCommand) -> Result<()> {
 
             println!("\nEnvironment variables:");
             for (key, value) in [
+                ("ATLASSIAN_PROFILE", std::env::var("ATLASSIAN_PROFILE").ok()),
                 ("ATLASSIAN_DOMAIN", std::env::var("ATLASSIAN_DOMAIN").ok()),
                 ("ATLASSIAN_EMAIL", std::env::var("ATLASSIAN_EMAIL").ok()),
+                (
+                    "ATLASSIAN_AUTH_TYPE",
+                    std::env::var("ATLASSIAN_AUTH_TYPE").ok(),
+                ),
                 (
                     "ATLASSIAN_API_TOKEN",
                     std::env::var("ATLASSIAN_API_TOKEN")
@@ -424,27 +1140,136 @@ async fn handle_config(cmd: ConfigCommand) -> Result<()> {
             println!("Config file edited: {:?}", path);
             Ok(())
         }
+        ConfigSubcommand::Migrate { global, yes } => {
+            let path = if let Some(path) = config_path {
+                Some(path.clone())
+            } else if global {
+                atlassian_cli::Config::global_config_path()
+            } else {
+                atlassian_cli::Config::project_config_path()
+                    .or_else(atlassian_cli::Config::global_config_path)
+            };
+            let path = path
+                .filter(|p| p.exists())
+                .ok_or_else(|| anyhow::anyhow!("Config file not found"))?;
+
+            let content = std::fs::read_to_string(&path)?;
+            let (rewritten, tokens) = atlassian_cli::Config::extract_tokens(&content);
+            if tokens.is_empty() {
+                println!("No plaintext token found in {:?}", path);
+                return Ok(());
+            }
+
+            // OS keyring storage is not supported yet; tokens move to the environment
+            println!("Plaintext tokens found in {:?}:\n", path);
+            for (profile, token) in &tokens {
+                if profile == "default" {
+                    println!("export ATLASSIAN_API_TOKEN={:?}", token);
+                } else {
+                    println!(
+                        "# profile '{}' (use with ATLASSIAN_PROFILE={})\nexport ATLASSIAN_API_TOKEN={:?}",
+                        profile, profile, token
+                    );
+                }
+            }
+            println!("\nAdd the export to your shell profile or secret manager.");
+
+            if !yes && !confirm(&format!("Comment out token lines in {:?}?", path))? {
+                println!("Config file unchanged.");
+                return Ok(());
+            }
+
+            std::fs::write(&path, rewritten)?;
+            println!("Commented out {} token line(s) in {:?}", tokens.len(), path);
+            Ok(())
+        }
+        ConfigSubcommand::Export => {
+            let config = atlassian_cli::Config::load_without_validation(
+                config_path,
+                profile,
+                domain,
+                email,
+                token,
+            )?;
+            print!("{}", config.to_shareable_toml()?);
+            Ok(())
+        }
+        ConfigSubcommand::Import { file, global, yes } => {
+            let incoming = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {:?}: {}", file, e))?;
+            let path = if global {
+                atlassian_cli::Config::global_config_path()
+            } else {
+                atlassian_cli::Config::project_config_path()
+                    .or_else(atlassian_cli::Config::global_config_path)
+            };
+            let path = path.ok_or_else(|| anyhow::anyhow!("Config file not found"))?;
+
+            let existing = if path.exists() {
+                std::fs::read_to_string(&path)?
+            } else {
+                String::new()
+            };
+            let merged = atlassian_cli::Config::merge_shared_toml(&existing, &incoming)?;
+
+            if !yes
+                && !confirm(&format!(
+                    "Merge {:?} into {:?}? (comments in the file are not kept)",
+                    file, path
+                ))?
+            {
+                println!("Config file unchanged.");
+                return Ok(());
+            }
+
+            if path.exists() {
+                let backup = path.with_extension("toml.bak");
+                std::fs::copy(&path, &backup)?;
+                println!("Backup written to {:?}", backup);
+            } else if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, merged)?;
+            println!("Imported {:?} into {:?}", file, path);
+            Ok(())
+        }
+        ConfigSubcommand::Doctor => {
+            let mut checks = atlassian_cli::Config::diagnose(
+                config_path,
+                profile,
+                domain.as_deref(),
+                email.as_deref(),
+                token.as_deref(),
+            );
+            checks.extend(doctor_connection(config_path, profile, domain, email, token).await);
+
+            for check in &checks {
+                println!("{} {}", if check.ok { "✓" } else { "✗" }, check.label);
+                if let Some(hint) = &check.hint {
+                    println!("    → {}", hint);
+                }
+            }
+
+            let failed = checks.iter().filter(|c| !c.ok).count();
+            if failed > 0 {
+                anyhow::bail!("{} of {} checks failed", failed, checks.len());
+            }
+            println!("\nAll {} checks passed", checks.len());
+            Ok(())
+        }
         ConfigSubcommand::Validate => {
             let config = atlassian_cli::Config::load(None, None, None, None, None)?;
 
             let client = reqwest::Client::new();
-            let url = format!("{}/rest/api/3/myself", config.base_url());
-
-            let response = client
-                .get(&url)
-                .header(
-                    "Authorization",
-                    format!(
-                        "Basic {}",
-                        base64::Engine::encode(
-                            &base64::engine::general_purpose::STANDARD,
-                            format!("{}:{}", config.email(), config.token())
-                        )
-                    ),
-                )
-                .header("Accept", "application/json")
-                .send()
-                .await?;
+            let url = format!("{}/myself", config.jira_api());
+
+            let response = atlassian_cli::http::send_timed(
+                client
+                    .get(&url)
+                    .header("Authorization", atlassian_cli::http::auth_header(&config))
+                    .header("Accept", "application/json"),
+            )
+            .await?;
 
             if response.status().is_success() {
                 let data: serde_json::Value = response.json().await?;
@@ -469,6 +1294,75 @@ async fn handle_config(cmd: ConfigCommand) -> Result<()> {
     }
 }
 
+/// Reachability and authentication checks for `config doctor`, using the resolved config
+async fn doctor_connection(
+    config_path: Option<&PathBuf>,
+    profile: Option<&String>,
+    domain: Option<String>,
+    email: Option<String>,
+    token: Option<String>,
+) -> Vec<atlassian_cli::config::Diagnosis> {
+    use atlassian_cli::config::Diagnosis;
+
+    let config = match atlassian_cli::Config::load(config_path, profile, domain, email, token) {
+        Ok(config) => config,
+        Err(e) => {
+            let reason = e.to_string();
+            let reason = reason.split(". ").next().unwrap_or_default();
+            return vec![Diagnosis::fail(
+                format!("Connection not checked: {}", reason),
+                "Fix the failures above, then rerun `config doctor`",
+            )];
+        }
+    };
+
+    let client = atlassian_cli::http::client(&config);
+    let url = format!("{}/myself", config.jira_api());
+    let response = match atlassian_cli::http::send_timed(
+        client
+            .get(&url)
+            .header("Authorization", atlassian_cli::http::auth_header(&config))
+            .header("Accept", "application/json"),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return vec![Diagnosis::fail(
+                format!("{} is not reachable: {}", config.base_url(), e),
+                "Check the domain, your network, and [performance] proxy / [http] ca_cert",
+            )];
+        }
+    };
+
+    let mut checks = vec![Diagnosis::pass(format!(
+        "{} is reachable",
+        config.base_url()
+    ))];
+    let status = response.status();
+    if status.is_success() {
+        let data: serde_json::Value = response.json().await.unwrap_or_default();
+        checks.push(Diagnosis::pass(format!(
+            "Authenticated as {}",
+            data["displayName"].as_str().unwrap_or("Unknown")
+        )));
+    } else {
+        let hint = match status.as_u16() {
+            401 => {
+                "Check the email and API token (https://id.atlassian.com/manage-profile/security/api-tokens)"
+            }
+            403 => "The account is authenticated but not allowed to use the REST API",
+            404 => "Check the domain and the `deployment` setting (cloud or datacenter)",
+            _ => "Rerun with -vv to see the full response",
+        };
+        checks.push(Diagnosis::fail(
+            format!("Authentication failed ({})", status),
+            hint,
+        ));
+    }
+    checks
+}
+
 async fn handle_jira(
     cmd: JiraCommand,
     config: &atlassian_cli::Config,
@@ -476,28 +1370,141 @@ async fn handle_jira(
     use atlassian_cli::jira;
 
     match cmd.subcommand {
-        JiraSubcommand::Get { issue_key, format } => {
-            let as_markdown = matches!(format, OutputFormat::Markdown);
-            jira::get_issue(&issue_key, as_markdown, config).await
+        JiraSubcommand::Get {
+            issue_key,
+            format,
+            no_comments,
+            no_attachments,
+            fields,
+            full_users,
+            expand,
+            ..
+        } => {
+            let as_markdown = format.as_markdown()?;
+            let options = jira::GetIssueOptions {
+                include_comments: !no_comments,
+                include_attachments: !no_attachments,
+                fields,
+                expand,
+                full_users,
+            };
+            jira::get_issue(&issue_key, as_markdown, options, config).await
+        }
+        JiraSubcommand::GetMany {
+            keys,
+            format,
+            comments,
+            attachments,
+            full_users,
+        } => {
+            let keys = read_issue_keys(keys)?;
+            let as_markdown = format.as_markdown()?;
+            jira::get_many(
+                &keys,
+                as_markdown,
+                comments,
+                attachments,
+                full_users,
+                config,
+            )
+            .await
         }
         JiraSubcommand::Search {
             jql,
+            jql_file,
             limit,
             all,
             stream,
+            max,
+            page_size,
+            partial_ok,
+            export,
+            jsonl,
+            page_token,
             fields,
+            since,
+            updated_since,
+            order,
+            count_only,
+            group_by,
+            fail_on_empty: _,
             format,
+            raw: _,
         } => {
             if stream && !all {
                 anyhow::bail!("--stream requires --all flag");
             }
-            let as_markdown = matches!(format, OutputFormat::Markdown);
+            if max.is_some() && !all {
+                anyhow::bail!("--max requires --all flag");
+            }
+            if partial_ok && !all {
+                anyhow::bail!("--partial-ok requires --all flag");
+            }
+            let paging = Paging { max, page_size };
+            let limit = limit.or(config.output.default_limit).unwrap_or(100);
+            let jql = read_query(jql, jql_file, "JQL")?;
+            let jql = jira::apply_since_filters(&jql, since.as_deref(), updated_since.as_deref())?;
+            let jql = jira::apply_order(&jql, &order)?;
+            if count_only {
+                return jira::count_issues(&jql, config).await;
+            }
+            if let Some(group_by) = group_by {
+                // Only the grouped field is needed
+                let fields = Some(vec![group_by.api_field().to_string()]);
+                let result = if all {
+                    jira::search_all(
+                        &jql,
+                        fields,
+                        Sink::Collect,
+                        paging,
+                        false,
+                        partial_ok,
+                        config,
+                    )
+                    .await?
+                } else {
+                    jira::search(&jql, limit, fields, page_token.as_deref(), false, config).await?
+                };
+                return Ok(jira::group_issues(&result, group_by));
+            }
+            let as_markdown = format.as_markdown()?;
             if all {
-                jira::search_all(&jql, fields, stream, as_markdown, config).await
+                let sink = match export {
+                    Some(path) => Sink::File(Export::create(&path, jsonl)?),
+                    None if stream => Sink::Stdout,
+                    None => Sink::Collect,
+                };
+                jira::search_all(&jql, fields, sink, paging, as_markdown, partial_ok, config).await
             } else {
-                jira::search(&jql, limit, fields, as_markdown, config).await
+                jira::search(
+                    &jql,
+                    limit,
+                    fields,
+                    page_token.as_deref(),
+                    as_markdown,
+                    config,
+                )
+                .await
             }
         }
+        JiraSubcommand::Watch {
+            jql,
+            jql_file,
+            interval,
+            fields,
+        } => {
+            let jql = read_query(jql, jql_file, "JQL")?;
+            jira::watch(
+                &jql,
+                std::time::Duration::from_secs(interval),
+                fields,
+                config,
+            )
+            .await
+        }
+        JiraSubcommand::BulkUpdate { from_stdin: _ } => {
+            jira::update_from_ndjson(std::io::stdin().lock(), config).await
+        }
         JiraSubcommand::Create {
             project,
             summary,
@@ -506,29 +1513,68 @@ async fn handle_jira(
             summary_flag,
             type_flag,
             description,
+            template,
+            dedupe,
+            from_stdin,
         } => {
+            if from_stdin {
+                return jira::create_from_ndjson(std::io::stdin().lock(), dedupe, config).await;
+            }
+            let template = match template {
+                Some(name) => config.jira.template(&name)?.clone(),
+                None => Default::default(),
+            };
             let proj = project
                 .or(project_flag)
+                .or(template.project)
                 .ok_or_else(|| anyhow::anyhow!("project required (positional or -p/--project)"))?;
             let summ = summary
                 .or(summary_flag)
+                .or(template.summary)
                 .ok_or_else(|| anyhow::anyhow!("summary required (positional or --summary)"))?;
             let itype = issue_type
                 .or(type_flag)
+                .or(template.issue_type)
                 .ok_or_else(|| anyhow::anyhow!("issue type required (positional or -t/--type)"))?;
-            let desc = description
-                .map(parse_text_or_adf)
-                .unwrap_or(serde_json::Value::Null);
-            jira::create_issue(&proj, &summ, &itype, desc, config).await
+            let desc = match (description, template.description) {
+                (Some(text), _) => parse_text_or_adf(text),
+                (None, Some(serde_json::Value::String(text))) => parse_text_or_adf(text),
+                (None, Some(adf)) => adf,
+                (None, None) => serde_json::Value::Null,
+            };
+            jira::create_issue(&proj, &summ, &itype, desc, &template.fields, dedupe, config).await
         }
-        JiraSubcommand::Update { issue_key, fields } => {
+        JiraSubcommand::Update(UpdateArgs {
+            issue_key,
+            fields,
+            from_file,
+        }) => {
+            let fields = match (fields, from_file) {
+                (Some(fields), None) => fields,
+                (None, Some(path)) => read_input(&path, "fields")?,
+                // clap rejects both together
+                _ => anyhow::bail!("fields JSON required (positional or --from-file)"),
+            };
             let fields_value: serde_json::Value = serde_json::from_str(&fields)?;
             jira::update_issue(&issue_key, fields_value, config).await
         }
+        JiraSubcommand::Label {
+            issue_key,
+            add,
+            remove,
+        } => jira::update_labels(&issue_key, &add, &remove, config).await,
         JiraSubcommand::Comments { issue_key, format } => {
-            let as_markdown = matches!(format, OutputFormat::Markdown);
+            let as_markdown = format.as_markdown()?;
             jira::get_comments(&issue_key, as_markdown, config).await
         }
+        JiraSubcommand::CommentGet {
+            issue_key,
+            comment_id,
+            format,
+        } => {
+            let as_markdown = format.as_markdown()?;
+            jira::get_comment(&issue_key, &comment_id, as_markdown, config).await
+        }
         JiraSubcommand::CommentAdd { issue_key, text } => {
             jira::add_comment(&issue_key, parse_text_or_adf(text), config).await
         }
@@ -542,13 +1588,22 @@ async fn handle_jira(
             transition_id,
             transition_id_flag,
         } => {
-            let tid = transition_id
-                .or(transition_id_flag)
-                .ok_or_else(|| anyhow::anyhow!("transition ID required (positional or --transition-id)"))?;
+            let tid = transition_id.or(transition_id_flag).ok_or_else(|| {
+                anyhow::anyhow!("transition ID required (positional or --transition-id)")
+            })?;
             jira::transition_issue(&issue_key, &tid, config).await
         }
-        JiraSubcommand::Transitions { issue_key } => {
-            jira::get_transitions(&issue_key, config).await
+        JiraSubcommand::Take { issue_key } => jira::assign_to_me(&issue_key, config).await,
+        JiraSubcommand::Start { issue_key } => jira::start_issue(&issue_key, config).await,
+        JiraSubcommand::Transitions { issue_key, expand } => {
+            jira::get_transitions(&issue_key, expand.is_some(), config).await
+        }
+        JiraSubcommand::CreateMeta {
+            project,
+            issue_type,
+        } => jira::get_create_meta(&project, &issue_type, config).await,
+        JiraSubcommand::Changelog { issue_key, field } => {
+            jira::get_changelog(&issue_key, field.as_deref(), config).await
         }
         JiraSubcommand::Attachments { issue_key } => {
             jira::get_attachments(&issue_key, config).await
@@ -556,9 +1611,44 @@ async fn handle_jira(
         JiraSubcommand::AttachmentDownload {
             attachment_id,
             output,
-        } => jira::download_attachment(&attachment_id, output.as_deref(), config).await,
-        JiraSubcommand::UserSearch { query, limit } => {
-            jira::search_users(&query, limit, config).await
+            overwrite,
+        } => jira::download_attachment(&attachment_id, output.as_deref(), overwrite, config).await,
+        JiraSubcommand::AttachmentDelete { attachment_id, yes } => {
+            if !yes && !confirm(&format!("Delete attachment {}?", attachment_id))? {
+                anyhow::bail!("Aborted: attachment {} not deleted", attachment_id);
+            }
+            jira::delete_attachment(&attachment_id, config).await
+        }
+        JiraSubcommand::AttachmentUpload { issue_key, file } => {
+            jira::upload_attachment(&issue_key, &file, config).await
+        }
+        JiraSubcommand::Sprints { board_id, state } => {
+            jira::get_sprints(board_id, state.as_deref(), config).await
+        }
+        JiraSubcommand::SprintIssues { sprint_id, fields } => {
+            jira::get_sprint_issues(sprint_id, fields, config).await
+        }
+        JiraSubcommand::MoveToSprint { sprint_id, keys } => {
+            let keys = read_issue_keys(keys)?;
+            jira::move_to_sprint(sprint_id, &keys, config).await
+        }
+        JiraSubcommand::Projects => jira::get_projects(config).await,
+        JiraSubcommand::Project { key } => jira::get_project(&key, config).await,
+        JiraSubcommand::Components { project } => jira::get_components(&project, config).await,
+        JiraSubcommand::ComponentCreate {
+            project,
+            name,
+            strict,
+        } => jira::create_component(&project, &name, strict, config).await,
+        JiraSubcommand::Versions { project } => jira::get_versions(&project, config).await,
+        JiraSubcommand::VersionCreate {
+            project,
+            name,
+            strict,
+        } => jira::create_version(&project, &name, strict, config).await,
+        JiraSubcommand::Myself => jira::get_myself(config).await,
+        JiraSubcommand::UserSearch { query, limit, all } => {
+            jira::search_users(&query, limit, all, config).await
         }
         JiraSubcommand::Filters {} => jira::get_filters(config).await,
         JiraSubcommand::FilterRun {
@@ -566,21 +1656,31 @@ async fn handle_jira(
             limit,
             all,
             stream,
+            max,
             fields,
             format,
         } => {
             if stream && !all {
                 anyhow::bail!("--stream requires --all flag");
             }
+            if max.is_some() && !all {
+                anyhow::bail!("--max requires --all flag");
+            }
+            let limit = limit.or(config.output.default_limit).unwrap_or(100);
             let filter_data = jira::get_filter(&filter, config).await?;
             let jql = filter_data["jql"]
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("Filter has no JQL query"))?;
-            let as_markdown = matches!(format, OutputFormat::Markdown);
+            let as_markdown = format.as_markdown()?;
             if all {
-                jira::search_all(jql, fields, stream, as_markdown, config).await
+                let sink = if stream { Sink::Stdout } else { Sink::Collect };
+                let paging = Paging {
+                    max,
+                    page_size: None,
+                };
+                jira::search_all(jql, fields, sink, paging, as_markdown, false, config).await
             } else {
-                jira::search(jql, limit, fields, as_markdown, config).await
+                jira::search(jql, limit, fields, None, as_markdown, config).await
             }
         }
     }
@@ -595,50 +1695,248 @@ async fn handle_confluence(
     match cmd.subcommand {
         ConfluenceSubcommand::Search {
             query,
+            cql_file,
             limit,
             all,
             stream,
+            max,
+            page_size,
+            cursor,
+            count_only,
+            content_type,
             expand,
+            fail_on_empty: _,
             format,
+            fields,
         } => {
             if stream && !all {
                 anyhow::bail!("--stream requires --all flag");
             }
-            let as_markdown = matches!(format, OutputFormat::Markdown);
-            if all {
-                confluence::search_all(&query, None, expand, stream, as_markdown, config).await
+            if max.is_some() && !all {
+                anyhow::bail!("--max requires --all flag");
+            }
+            let limit = limit.or(config.output.default_limit).unwrap_or(10);
+            let query = read_query(query, cql_file, "CQL")?;
+            let query = confluence::apply_type_filter(&query, content_type.as_deref());
+            if count_only {
+                return confluence::count(&query, config).await;
+            }
+            let as_markdown = format.as_markdown()?;
+            let mut result = if all {
+                let paging = Paging { max, page_size };
+                confluence::search_all(&query, None, expand, stream, paging, as_markdown, config)
+                    .await?
             } else {
-                confluence::search(&query, limit, None, expand, as_markdown, config).await
+                confluence::search(
+                    &query,
+                    limit,
+                    None,
+                    expand,
+                    cursor.as_deref(),
+                    as_markdown,
+                    config,
+                )
+                .await?
+            };
+            if let Some(fields) = fields {
+                confluence::project_fields(&mut result, &fields);
             }
+            Ok(result)
         }
-        ConfluenceSubcommand::Get { page_id, format } => {
+        ConfluenceSubcommand::Get {
+            page_id,
+            format,
+            version,
+        } => {
+            let as_markdown = matches!(format, OutputFormat::Markdown);
+            let mut page = match version {
+                Some(n) => confluence::get_page_version(&page_id, n, as_markdown, config).await?,
+                None => confluence::get_page(&page_id, None, None, as_markdown, config).await?,
+            };
+            if matches!(format, OutputFormat::Text) {
+                confluence::convert_page_to_text(&mut page);
+            }
+            Ok(page)
+        }
+        ConfluenceSubcommand::GetByTitle {
+            space,
+            title,
+            format,
+        } => {
+            let page_id = confluence::find_page_by_title(&space, &title, config).await?;
             let as_markdown = matches!(format, OutputFormat::Markdown);
-            confluence::get_page(&page_id, None, None, as_markdown, config).await
+            let mut page = confluence::get_page(&page_id, None, None, as_markdown, config).await?;
+            if matches!(format, OutputFormat::Text) {
+                confluence::convert_page_to_text(&mut page);
+            }
+            Ok(page)
         }
         ConfluenceSubcommand::Create {
             space,
             title,
             content,
-        } => confluence::create_page(&space, &title, &content, None, None, config).await,
+            parent_title,
+        } => {
+            let parent_id = match &parent_title {
+                Some(parent) => Some(confluence::find_page_by_title(&space, parent, config).await?),
+                None => None,
+            };
+            confluence::create_page(
+                &space,
+                &title,
+                &content,
+                parent_id.as_deref(),
+                None,
+                None,
+                config,
+            )
+            .await
+        }
         ConfluenceSubcommand::Update {
             page_id,
             title,
             content,
-        } => confluence::update_page(&page_id, &title, &content, None, None, config).await,
-        ConfluenceSubcommand::Children { page_id } => {
-            confluence::get_page_children(&page_id, config).await
+        } => {
+            confluence::update_page(
+                &page_id,
+                title.as_deref(),
+                content.as_deref(),
+                None,
+                None,
+                config,
+            )
+            .await
         }
-        ConfluenceSubcommand::Comments { page_id, format } => {
-            let as_markdown = matches!(format, OutputFormat::Markdown);
-            confluence::get_comments(&page_id, as_markdown, config).await
+        ConfluenceSubcommand::Children {
+            page_id,
+            recursive,
+            max_depth,
+        } => {
+            if recursive {
+                confluence::get_page_descendants(&page_id, max_depth, config).await
+            } else {
+                confluence::get_page_children(&page_id, config).await
+            }
+        }
+        ConfluenceSubcommand::Comments {
+            page_id,
+            inline,
+            all,
+            format,
+        } => {
+            let as_markdown = format.as_markdown()?;
+            confluence::get_comments(&page_id, inline, all, as_markdown, config).await
+        }
+        ConfluenceSubcommand::Versions { page_id } => {
+            confluence::get_versions(&page_id, config).await
+        }
+        ConfluenceSubcommand::Attachments { page_id, action } => match action {
+            Some(AttachmentsSubcommand::List { page_id }) => {
+                confluence::get_attachments(&page_id, config).await
+            }
+            Some(AttachmentsSubcommand::Download(args)) => {
+                download_confluence_attachment(args, config).await
+            }
+            None => {
+                // clap shows help when neither a page id nor a subcommand is given
+                let page_id = page_id.ok_or_else(|| anyhow::anyhow!("Page ID required"))?;
+                confluence::get_attachments(&page_id, config).await
+            }
+        },
+        ConfluenceSubcommand::AttachmentDownload(args) => {
+            download_confluence_attachment(args, config).await
         }
     }
 }
 
-fn output_json(value: &serde_json::Value, pretty: bool) {
-    if pretty {
-        println!("{}", serde_json::to_string_pretty(value).unwrap());
-    } else {
-        println!("{}", serde_json::to_string(value).unwrap());
+async fn download_confluence_attachment(
+    args: AttachmentDownloadArgs,
+    config: &atlassian_cli::Config,
+) -> Result<serde_json::Value> {
+    atlassian_cli::confluence::download_attachment(
+        &args.attachment_id,
+        args.output.as_deref(),
+        args.overwrite,
+        config,
+    )
+    .await
+}
+
+/// Resolve a search query from the positional argument, `--*-file`, or stdin (`-`)
+fn read_query(positional: Option<String>, file: Option<PathBuf>, kind: &str) -> Result<String> {
+    let query = match (positional, file) {
+        (Some(q), None) if q != "-" => q,
+        (Some(_), None) => std::io::read_to_string(std::io::stdin())?,
+        (None, Some(path)) => read_input(&path, kind)?,
+        // clap rejects a positional query together with a file
+        _ => anyhow::bail!(
+            "{} query required (positional, - for stdin, or a file)",
+            kind
+        ),
+    };
+
+    // An empty query is checked after project/space filter injection
+    Ok(query.trim().to_string())
+}
+
+/// Contents of a file, or stdin when the path is `-`
+fn read_input(path: &Path, kind: &str) -> Result<String> {
+    if path.as_os_str() == "-" {
+        return Ok(std::io::read_to_string(std::io::stdin())?);
+    }
+    std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {} file {:?}: {}", kind, path, e))
+}
+
+/// Drop null values recursively (TOML cannot represent them)
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Issue keys from arguments, or from stdin (whitespace/comma separated) when none or `-`
+fn read_issue_keys(keys: Vec<String>) -> Result<Vec<String>> {
+    if !keys.is_empty() && keys != ["-"] {
+        return Ok(keys);
+    }
+    Ok(std::io::read_to_string(std::io::stdin())?
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|k| !k.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Ask a yes/no question on stderr; anything but `y`/`yes` is a no
+fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn output_result(
+    value: &serde_json::Value,
+    output: OutputEncoding,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    match output {
+        OutputEncoding::Json if pretty => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputEncoding::Json => println!("{}", serde_json::to_string(value)?),
+        // Multiline strings (e.g. markdown bodies) are emitted as `|` block scalars
+        OutputEncoding::Yaml => print!("{}", serde_norway::to_string(value)?),
+        OutputEncoding::Table => {
+            let color = atlassian_cli::output::use_color(no_color);
+            print!("{}", atlassian_cli::output::render_table(value, color))
+        }
     }
+    Ok(())
 }
@@ -1,9 +1,11 @@
-use super::inline::convert_inline_nodes;
+use super::AdfOptions;
+use super::inline::{InlineContext, convert_inline_nodes, convert_macro};
+use crate::config::ExpandStyle;
 use serde_json::Value;
 
 const MAX_DEPTH: usize = 50;
 
-pub fn convert_block_node(node: &Value, depth: usize) -> Option<String> {
+pub fn convert_block_node(node: &Value, depth: usize, opts: &AdfOptions) -> Option<String> {
     if depth > MAX_DEPTH {
         return Some("[Content truncated: max depth exceeded]".into());
     }
@@ -11,29 +13,30 @@ pub fn convert_block_node(node: &Value, depth: usize) -> Option<String> {
     let node_type = node.get("type")?.as_str()?;
 
     match node_type {
-        "paragraph" => convert_paragraph(node),
-        "heading" => convert_heading(node),
-        "bulletList" => convert_bullet_list(node, depth),
-        "orderedList" => convert_ordered_list(node, depth),
-        "listItem" => convert_list_item(node, depth),
+        "paragraph" => convert_paragraph(node, InlineContext::Block, opts),
+        "heading" => convert_heading(node, opts),
+        "bulletList" => convert_bullet_list(node, depth, opts),
+        "orderedList" => convert_ordered_list(node, depth, opts),
+        "listItem" => convert_list_item(node, depth, opts),
         "codeBlock" => convert_code_block(node),
-        "blockquote" => convert_blockquote(node),
+        "blockquote" => convert_blockquote(node, opts),
         "rule" => Some("---".into()),
-        "panel" => convert_panel(node),
-        "table" => convert_table(node),
+        "panel" => convert_panel(node, opts),
+        "table" => convert_table(node, opts),
         "mediaSingle" | "mediaGroup" => convert_media(node),
-        "expand" | "nestedExpand" => convert_expand(node),
-        "taskList" => convert_task_list(node),
-        "taskItem" => convert_task_item(node),
-        "decisionList" => convert_decision_list(node),
-        "decisionItem" => convert_decision_item(node),
-        "layoutSection" => convert_layout_section(node),
-        "layoutColumn" => convert_layout_column(node),
+        "expand" | "nestedExpand" => convert_expand(node, depth, opts),
+        "taskList" => convert_task_list(node, opts),
+        "taskItem" => convert_task_item(node, opts),
+        "decisionList" => convert_decision_list(node, opts),
+        "decisionItem" => convert_decision_item(node, opts),
+        "layoutSection" => convert_layout_section(node, opts),
+        "layoutColumn" => convert_layout_column(node, opts),
         "embedCard" => convert_embed_card(node),
-        "bodiedExtension" | "multiBodiedExtension" => convert_extension(node),
-        "extensionFrame" => convert_extension_frame(node),
+        "bodiedExtension" | "multiBodiedExtension" => convert_extension(node, opts),
+        "extension" => Some(convert_macro(node)),
+        "extensionFrame" => convert_extension_frame(node, opts),
         unknown => {
-            let content = convert_children(node);
+            let content = convert_children(node, opts);
             if content.is_empty() {
                 None
             } else {
@@ -43,21 +46,21 @@ pub fn convert_block_node(node: &Value, depth: usize) -> Option<String> {
     }
 }
 
-fn convert_children(node: &Value) -> String {
+fn convert_children(node: &Value, opts: &AdfOptions) -> String {
     node.get("content")
         .and_then(|c| c.as_array())
         .map(|arr| {
             arr.iter()
-                .filter_map(|n| convert_block_node(n, 0))
+                .filter_map(|n| convert_block_node(n, 0, opts))
                 .collect::<Vec<_>>()
                 .join("\n\n")
         })
         .unwrap_or_default()
 }
 
-fn convert_paragraph(node: &Value) -> Option<String> {
+fn convert_paragraph(node: &Value, ctx: InlineContext, opts: &AdfOptions) -> Option<String> {
     let content = node.get("content")?.as_array()?;
-    let text = convert_inline_nodes(content);
+    let text = convert_inline_nodes(content, ctx, opts);
     if text.trim().is_empty() {
         None
     } else {
@@ -65,30 +68,32 @@ fn convert_paragraph(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_heading(node: &Value) -> Option<String> {
+fn convert_heading(node: &Value, opts: &AdfOptions) -> Option<String> {
     let level = node
         .get("attrs")
         .and_then(|a| a.get("level"))
         .and_then(|l| l.as_u64())
-        .unwrap_or(1) as usize;
+        .unwrap_or(1);
+    // attrs.level comes from the document and may be any u64
+    let level = level.saturating_add(u64::from(opts.heading_offset)).min(6) as usize;
 
     let content = node.get("content").and_then(|c| c.as_array())?;
-    let text = convert_inline_nodes(content);
+    let text = convert_inline_nodes(content, InlineContext::SingleLine, opts);
 
     if text.trim().is_empty() {
         None
     } else {
-        Some(format!("{} {}", "#".repeat(level.min(6)), text))
+        Some(format!("{} {}", "#".repeat(level), text))
     }
 }
 
-fn convert_bullet_list(node: &Value, depth: usize) -> Option<String> {
+fn convert_bullet_list(node: &Value, depth: usize, opts: &AdfOptions) -> Option<String> {
     let items = node.get("content")?.as_array()?;
     let lines: Vec<String> = items
         .iter()
         .filter_map(|item| {
-            let content = convert_list_item(item, depth)?;
-            Some(format!("{}- {}", "  ".repeat(depth), content))
+            let content = convert_list_item(item, depth, opts)?;
+            Some(format!("- {}", content))
         })
         .collect();
 
@@ -99,14 +104,14 @@ fn convert_bullet_list(node: &Value, depth: usize) -> Option<String> {
     }
 }
 
-fn convert_ordered_list(node: &Value, depth: usize) -> Option<String> {
+fn convert_ordered_list(node: &Value, depth: usize, opts: &AdfOptions) -> Option<String> {
     let items = node.get("content")?.as_array()?;
     let lines: Vec<String> = items
         .iter()
         .enumerate()
         .filter_map(|(i, item)| {
-            let content = convert_list_item(item, depth)?;
-            Some(format!("{}{}. {}", "  ".repeat(depth), i + 1, content))
+            let content = convert_list_item(item, depth, opts)?;
+            Some(format!("{}. {}", i + 1, content))
         })
         .collect();
 
@@ -117,7 +122,7 @@ fn convert_ordered_list(node: &Value, depth: usize) -> Option<String> {
     }
 }
 
-fn convert_list_item(node: &Value, depth: usize) -> Option<String> {
+fn convert_list_item(node: &Value, depth: usize, opts: &AdfOptions) -> Option<String> {
     let content = node.get("content")?.as_array()?;
     let mut parts: Vec<String> = Vec::new();
 
@@ -125,22 +130,23 @@ fn convert_list_item(node: &Value, depth: usize) -> Option<String> {
         let child_type = child.get("type").and_then(|t| t.as_str()).unwrap_or("");
         match child_type {
             "paragraph" => {
-                if let Some(text) = convert_paragraph(child) {
-                    parts.push(text);
+                // Indent hardBreak continuation lines so they stay inside the item
+                if let Some(text) = convert_paragraph(child, InlineContext::Block, opts) {
+                    parts.push(text.replace('\n', "\n  "));
                 }
             }
             "bulletList" => {
-                if let Some(list) = convert_bullet_list(child, depth + 1) {
-                    parts.push(format!("\n{}", list));
+                if let Some(list) = convert_bullet_list(child, depth + 1, opts) {
+                    parts.push(format!("\n{}", indent_lines(&list)));
                 }
             }
             "orderedList" => {
-                if let Some(list) = convert_ordered_list(child, depth + 1) {
-                    parts.push(format!("\n{}", list));
+                if let Some(list) = convert_ordered_list(child, depth + 1, opts) {
+                    parts.push(format!("\n{}", indent_lines(&list)));
                 }
             }
             _ => {
-                if let Some(text) = convert_block_node(child, depth + 1) {
+                if let Some(text) = convert_block_node(child, depth + 1, opts) {
                     parts.push(text);
                 }
             }
@@ -154,6 +160,15 @@ fn convert_list_item(node: &Value, depth: usize) -> Option<String> {
     }
 }
 
+/// Indent a nested list one level under its parent item. Lists are rendered relative to
+/// themselves, so `depth` only guards nesting and never shifts a list inside a container.
+fn indent_lines(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn convert_code_block(node: &Value) -> Option<String> {
     let language = node
         .get("attrs")
@@ -173,14 +188,18 @@ fn convert_code_block(node: &Value) -> Option<String> {
         })
         .unwrap_or_default();
 
+    // The closing fence goes on its own line; a trailing newline in the code would
+    // otherwise leave a blank line before it
+    let code = code.strip_suffix('\n').unwrap_or(&code);
+
     Some(format!("```{}\n{}\n```", language, code))
 }
 
-fn convert_blockquote(node: &Value) -> Option<String> {
+fn convert_blockquote(node: &Value, opts: &AdfOptions) -> Option<String> {
     let content = node.get("content")?.as_array()?;
     let lines: Vec<String> = content
         .iter()
-        .filter_map(|child| convert_block_node(child, 0))
+        .filter_map(|child| convert_block_node(child, 0, opts))
         .flat_map(|text| text.lines().map(|l| format!("> {}", l)).collect::<Vec<_>>())
         .collect();
 
@@ -191,7 +210,7 @@ fn convert_blockquote(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_panel(node: &Value) -> Option<String> {
+fn convert_panel(node: &Value, opts: &AdfOptions) -> Option<String> {
     let panel_type = node
         .get("attrs")
         .and_then(|a| a.get("panelType"))
@@ -202,7 +221,7 @@ fn convert_panel(node: &Value) -> Option<String> {
     let content = node.get("content")?.as_array()?;
     let text: Vec<String> = content
         .iter()
-        .filter_map(|child| convert_block_node(child, 0))
+        .filter_map(|child| convert_block_node(child, 0, opts))
         .collect();
 
     if text.is_empty() {
@@ -212,28 +231,47 @@ fn convert_panel(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_table(node: &Value) -> Option<String> {
+fn convert_table(node: &Value, opts: &AdfOptions) -> Option<String> {
     let rows = node.get("content")?.as_array()?;
     if rows.is_empty() {
         return None;
     }
 
-    let mut result: Vec<String> = Vec::new();
-    let mut col_count = 0;
-    let mut is_first_row = true;
+    let mut table: Vec<Vec<String>> = Vec::with_capacity(rows.len());
+    let mut alignments: Vec<&'static str> = Vec::new();
+    let mut first_row_is_header = false;
+    let mut left_header = true;
 
-    for row in rows {
+    for (row_idx, row) in rows.iter().enumerate() {
         let cells = row.get("content").and_then(|c| c.as_array())?;
         let mut row_cells: Vec<String> = Vec::new();
 
-        for cell in cells {
+        for (cell_idx, cell) in cells.iter().enumerate() {
+            let is_header = cell.get("type").and_then(|t| t.as_str()) == Some("tableHeader");
+            if row_idx == 0 {
+                first_row_is_header = if cell_idx == 0 {
+                    is_header
+                } else {
+                    first_row_is_header && is_header
+                };
+            }
+            if cell_idx == 0 && !is_header {
+                left_header = false;
+            }
+
             let attrs = cell.get("attrs");
             let colspan = attrs
                 .and_then(|a| a.get("colspan"))
                 .and_then(|c| c.as_u64())
                 .unwrap_or(1) as usize;
 
-            let content = convert_cell_content(cell);
+            let content = convert_cell_content(cell, opts);
+
+            // Column alignment comes from the first row; spanned columns keep the default
+            if row_idx == 0 {
+                alignments.push(alignment_marker(cell));
+                alignments.extend(std::iter::repeat_n("---", colspan.saturating_sub(1)));
+            }
 
             for i in 0..colspan {
                 if i == 0 {
@@ -244,56 +282,139 @@ fn convert_table(node: &Value) -> Option<String> {
             }
         }
 
-        if is_first_row {
-            col_count = row_cells.len();
-        }
+        table.push(row_cells);
+    }
 
-        result.push(format!("| {} |", row_cells.join(" | ")));
+    // Pad every row to the widest one so columns stay aligned
+    let col_count = table.iter().map(Vec::len).max().unwrap_or(0);
+    if col_count == 0 {
+        return None;
+    }
+    for row in &mut table {
+        row.resize(col_count, String::new());
+    }
 
-        if is_first_row {
-            let separator: Vec<&str> = vec!["---"; col_count];
-            result.push(format!("| {} |", separator.join(" | ")));
-            is_first_row = false;
+    let mut omitted = 0;
+    alignments.resize(col_count, "---");
+    let col_count = match opts.table_max_cols {
+        Some(max) if max > 0 && col_count > max => {
+            omitted = col_count - max;
+            for row in &mut table {
+                row.truncate(max);
+            }
+            alignments.truncate(max);
+            max
         }
+        _ => col_count,
+    };
+
+    let format_row = |cells: &[String]| format!("| {} |", cells.join(" | "));
+    let separator = format!("| {} |", alignments.join(" | "));
+    let mut result: Vec<String> = Vec::with_capacity(table.len() + 2);
+    let mut body = table.iter();
+
+    // Row headers (tableHeader down the left side) still use row one as the GFM header;
+    // tables without any header cells get an empty header row so the output stays valid
+    if first_row_is_header || left_header {
+        result.push(format_row(body.next()?));
+    } else {
+        result.push(format_row(&vec![String::new(); col_count]));
+    }
+    result.push(separator);
+    result.extend(body.map(|row| format_row(row)));
+
+    if omitted > 0 {
+        let plural = if omitted == 1 { "" } else { "s" };
+        result.push(format!("\n_{} more column{} not shown_", omitted, plural));
     }
 
     Some(result.join("\n"))
 }
 
-fn convert_cell_content(cell: &Value) -> String {
-    let content = cell
+/// GFM separator for a cell's alignment, read from an `align` cell attr or the
+/// `alignment` mark on its first block. Unaligned cells use the plain (left) `---`.
+fn alignment_marker(cell: &Value) -> &'static str {
+    let attr_align = cell
+        .get("attrs")
+        .and_then(|a| a.get("align").or_else(|| a.get("alignment")))
+        .and_then(|a| a.as_str());
+    let mark_align = || {
+        cell.get("content")?
+            .as_array()?
+            .first()?
+            .get("marks")?
+            .as_array()?
+            .iter()
+            .find(|m| m.get("type").and_then(|t| t.as_str()) == Some("alignment"))?
+            .get("attrs")?
+            .get("align")?
+            .as_str()
+    };
+
+    match attr_align.or_else(mark_align) {
+        Some("center") => ":---:",
+        Some("end" | "right") => "---:",
+        Some("start" | "left") => ":---",
+        _ => "---",
+    }
+}
+
+/// Cell content on a single markdown table row: blocks are joined with `<br>`, rules are
+/// dropped, and newlines inside blocks (lists, code) become `<br>` as well.
+/// With `cell_max_chars`, longer content is cut before escaping so `<br>` and `\|` stay intact.
+fn convert_cell_content(cell: &Value, opts: &AdfOptions) -> String {
+    let blocks: Vec<String> = cell
         .get("content")
         .and_then(|c| c.as_array())
-        .map(|content| {
-            content
-                .iter()
-                .filter_map(|n| convert_block_node(n, 0))
-                .collect::<Vec<_>>()
-                .join(" ")
+        .into_iter()
+        .flatten()
+        .filter_map(|n| match n.get("type").and_then(|t| t.as_str()) {
+            Some("paragraph") => convert_paragraph(n, InlineContext::SingleLine, opts),
+            Some("rule") => None,
+            _ => convert_block_node(n, 0, opts),
         })
-        .unwrap_or_default();
+        .map(|block| block.trim().to_string())
+        .filter(|block| !block.is_empty())
+        .collect();
+
+    let mut text = blocks.join("\n").replace("\r\n", "\n");
+    if let Some(max) = opts.cell_max_chars
+        && let Some((cut, _)) = text.char_indices().nth(max)
+    {
+        text.truncate(cut);
+        text = format!("{}…", text.trim_end());
+    }
 
     // Escape pipe characters to prevent breaking table structure
-    content.replace('|', "\\|")
+    text.replace('\n', "<br>").replace('|', "\\|")
 }
 
 fn convert_media(node: &Value) -> Option<String> {
     let content = node.get("content").and_then(|c| c.as_array())?;
 
-    for media in content {
-        if let Some(attrs) = media.get("attrs") {
+    // One reference per item so every file in a mediaGroup gets an attachment link
+    let refs: Vec<String> = content
+        .iter()
+        .filter(|media| media.get("type").and_then(|t| t.as_str()) != Some("caption"))
+        .filter_map(|media| {
+            let attrs = media.get("attrs")?;
             let alt = attrs
                 .get("alt")
                 .and_then(|a| a.as_str())
                 .or_else(|| attrs.get("id").and_then(|i| i.as_str()))
                 .unwrap_or("media");
-            return Some(format!("[Media: {}]", alt));
-        }
+            Some(format!("[Media: {}]", alt))
+        })
+        .collect();
+
+    if refs.is_empty() {
+        Some("[Media]".into())
+    } else {
+        Some(refs.join("\n"))
     }
-    Some("[Media]".into())
 }
 
-fn convert_expand(node: &Value) -> Option<String> {
+fn convert_expand(node: &Value, depth: usize, opts: &AdfOptions) -> Option<String> {
     let title = node
         .get("attrs")
         .and_then(|a| a.get("title"))
@@ -303,19 +424,36 @@ fn convert_expand(node: &Value) -> Option<String> {
     let content = node.get("content")?.as_array()?;
     let text: Vec<String> = content
         .iter()
-        .filter_map(|child| convert_block_node(child, 0))
+        .filter_map(|child| convert_block_node(child, depth + 1, opts))
         .collect();
 
     if text.is_empty() {
-        None
-    } else {
-        Some(format!("**{}**\n\n{}", title, text.join("\n\n")))
+        return None;
     }
+    Some(match opts.expand_style {
+        // Blank lines around the body let renderers parse the markdown inside the HTML block;
+        // nested expands become nested <details>
+        ExpandStyle::Details => format!(
+            "<details>\n<summary>{}</summary>\n\n{}\n\n</details>",
+            escape_html(title),
+            text.join("\n\n")
+        ),
+        ExpandStyle::Bold => format!("**{}**\n\n{}", title, text.join("\n\n")),
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
 }
 
-fn convert_task_list(node: &Value) -> Option<String> {
+fn convert_task_list(node: &Value, opts: &AdfOptions) -> Option<String> {
     let items = node.get("content")?.as_array()?;
-    let lines: Vec<String> = items.iter().filter_map(convert_task_item).collect();
+    let lines: Vec<String> = items
+        .iter()
+        .filter_map(|n| convert_task_item(n, opts))
+        .collect();
 
     if lines.is_empty() {
         None
@@ -324,7 +462,7 @@ fn convert_task_list(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_task_item(node: &Value) -> Option<String> {
+fn convert_task_item(node: &Value, opts: &AdfOptions) -> Option<String> {
     let attrs = node.get("attrs");
     let state = attrs
         .and_then(|a| a.get("state"))
@@ -338,7 +476,7 @@ fn convert_task_item(node: &Value) -> Option<String> {
         .and_then(|c| c.as_array())
         .map(|arr| {
             arr.iter()
-                .filter_map(|n| convert_block_node(n, 0))
+                .filter_map(|n| convert_block_node(n, 0, opts))
                 .collect::<Vec<_>>()
                 .join(" ")
         })
@@ -347,9 +485,12 @@ fn convert_task_item(node: &Value) -> Option<String> {
     Some(format!("- {} {}", checkbox, content))
 }
 
-fn convert_decision_list(node: &Value) -> Option<String> {
+fn convert_decision_list(node: &Value, opts: &AdfOptions) -> Option<String> {
     let items = node.get("content")?.as_array()?;
-    let lines: Vec<String> = items.iter().filter_map(convert_decision_item).collect();
+    let lines: Vec<String> = items
+        .iter()
+        .filter_map(|n| convert_decision_item(n, opts))
+        .collect();
 
     if lines.is_empty() {
         None
@@ -358,7 +499,7 @@ fn convert_decision_list(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_decision_item(node: &Value) -> Option<String> {
+fn convert_decision_item(node: &Value, opts: &AdfOptions) -> Option<String> {
     let attrs = node.get("attrs");
     let state = attrs
         .and_then(|a| a.get("state"))
@@ -372,7 +513,7 @@ fn convert_decision_item(node: &Value) -> Option<String> {
         .and_then(|c| c.as_array())
         .map(|arr| {
             arr.iter()
-                .filter_map(|n| convert_block_node(n, 0))
+                .filter_map(|n| convert_block_node(n, 0, opts))
                 .collect::<Vec<_>>()
                 .join(" ")
         })
@@ -381,9 +522,12 @@ fn convert_decision_item(node: &Value) -> Option<String> {
     Some(format!("- {} {}", icon, content))
 }
 
-fn convert_layout_section(node: &Value) -> Option<String> {
+fn convert_layout_section(node: &Value, opts: &AdfOptions) -> Option<String> {
     let content = node.get("content")?.as_array()?;
-    let columns: Vec<String> = content.iter().filter_map(convert_layout_column).collect();
+    let columns: Vec<String> = content
+        .iter()
+        .filter_map(|n| convert_layout_column(n, opts))
+        .collect();
 
     if columns.is_empty() {
         None
@@ -392,17 +536,25 @@ fn convert_layout_section(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_layout_column(node: &Value) -> Option<String> {
+fn convert_layout_column(node: &Value, opts: &AdfOptions) -> Option<String> {
     let content = node.get("content")?.as_array()?;
     let text: Vec<String> = content
         .iter()
-        .filter_map(|n| convert_block_node(n, 0))
+        .filter_map(|n| convert_block_node(n, 0, opts))
         .collect();
 
     if text.is_empty() {
-        None
-    } else {
-        Some(text.join("\n\n"))
+        return None;
+    }
+    let body = text.join("\n\n");
+    // Keep the column ratio (e.g. 70/30) visible, since markdown has no columns
+    match node
+        .get("attrs")
+        .and_then(|a| a.get("width"))
+        .and_then(|w| w.as_f64())
+    {
+        Some(width) => Some(format!("<!-- column width={}% -->\n{}", width, body)),
+        None => Some(body),
     }
 }
 
@@ -417,14 +569,14 @@ fn convert_embed_card(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_extension(node: &Value) -> Option<String> {
+fn convert_extension(node: &Value, opts: &AdfOptions) -> Option<String> {
     let attrs = node.get("attrs");
     let extension_type = attrs
         .and_then(|a| a.get("extensionType"))
         .and_then(|t| t.as_str())
         .unwrap_or("extension");
 
-    let content = convert_children(node);
+    let content = convert_children(node, opts);
     if content.is_empty() {
         Some(format!("[Extension: {}]", extension_type))
     } else {
@@ -432,8 +584,8 @@ fn convert_extension(node: &Value) -> Option<String> {
     }
 }
 
-fn convert_extension_frame(node: &Value) -> Option<String> {
-    convert_children(node).into()
+fn convert_extension_frame(node: &Value, opts: &AdfOptions) -> Option<String> {
+    convert_children(node, opts).into()
 }
 
 #[cfg(test)]
@@ -447,7 +599,10 @@ mod tests {
             "type": "paragraph",
             "content": [{"type": "text", "text": "Hello world"}]
         });
-        assert_eq!(convert_block_node(&node, 0), Some("Hello world".into()));
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("Hello world".into())
+        );
     }
 
     #[test]
@@ -457,7 +612,163 @@ mod tests {
             "attrs": {"level": 2},
             "content": [{"type": "text", "text": "Title"}]
         });
-        assert_eq!(convert_block_node(&node, 0), Some("## Title".into()));
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("## Title".into())
+        );
+    }
+
+    #[test]
+    fn test_heading_offset() {
+        let opts = AdfOptions {
+            heading_offset: 2,
+            ..Default::default()
+        };
+        let heading = |level: u64| {
+            json!({
+                "type": "heading",
+                "attrs": {"level": level},
+                "content": [{"type": "text", "text": "Title"}]
+            })
+        };
+        assert_eq!(
+            convert_block_node(&heading(1), 0, &opts),
+            Some("### Title".into())
+        );
+        assert_eq!(
+            convert_block_node(&heading(5), 0, &opts),
+            Some("###### Title".into())
+        );
+        // Malformed levels clamp instead of overflowing
+        assert_eq!(
+            convert_block_node(&heading(u64::MAX), 0, &opts),
+            Some("###### Title".into())
+        );
+    }
+
+    #[test]
+    fn test_heading_hard_break_stays_on_one_line() {
+        let node = json!({
+            "type": "heading",
+            "attrs": {"level": 1},
+            "content": [
+                {"type": "text", "text": "Release"},
+                {"type": "hardBreak"},
+                {"type": "text", "text": "notes"}
+            ]
+        });
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("# Release notes".into())
+        );
+    }
+
+    #[test]
+    fn test_list_item_hard_break_is_indented() {
+        let node = json!({
+            "type": "bulletList",
+            "content": [
+                {"type": "listItem", "content": [{"type": "paragraph", "content": [
+                    {"type": "text", "text": "Line 1"},
+                    {"type": "hardBreak"},
+                    {"type": "text", "text": "Line 2"}
+                ]}]}
+            ]
+        });
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("- Line 1\n  Line 2".into())
+        );
+    }
+
+    #[test]
+    fn test_table_cell_hard_break_becomes_space() {
+        let cell = json!({
+            "type": "tableCell",
+            "content": [{"type": "paragraph", "content": [
+                {"type": "text", "text": "a"},
+                {"type": "hardBreak"},
+                {"type": "text", "text": "b"}
+            ]}]
+        });
+        assert_eq!(convert_cell_content(&cell, &AdfOptions::default()), "a b");
+    }
+
+    #[test]
+    fn test_table_cell_multiple_blocks_stay_on_one_row() {
+        let cell = json!({
+            "type": "tableCell",
+            "content": [
+                {"type": "paragraph", "content": [{"type": "text", "text": "First | part"}]},
+                {"type": "rule"},
+                {"type": "paragraph", "content": [{"type": "text", "text": "Second"}]},
+                {"type": "bulletList", "content": [
+                    {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "x"}]}]},
+                    {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "y"}]}]}
+                ]}
+            ]
+        });
+        assert_eq!(
+            convert_cell_content(&cell, &AdfOptions::default()),
+            "First \\| part<br>Second<br>- x<br>- y"
+        );
+    }
+
+    #[test]
+    fn test_table_cell_max_chars() {
+        let cell = json!({
+            "type": "tableCell",
+            "content": [
+                {"type": "paragraph", "content": [{"type": "text", "text": "abc"}]},
+                {"type": "paragraph", "content": [{"type": "text", "text": "d|efgh"}]}
+            ]
+        });
+        let opts = AdfOptions {
+            cell_max_chars: Some(6),
+            ..Default::default()
+        };
+        assert_eq!(convert_cell_content(&cell, &opts), "abc<br>d\\|…");
+
+        let opts = AdfOptions {
+            cell_max_chars: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(convert_cell_content(&cell, &opts), "abc<br>d\\|efgh");
+    }
+
+    #[test]
+    fn test_table_max_cols_drops_extra_columns() {
+        let cell = |text: &str| json!({"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": text}]}]});
+        let node = json!({
+            "type": "table",
+            "content": [
+                {"type": "tableRow", "content": [cell("1"), cell("2"), cell("3"), cell("4")]}
+            ]
+        });
+        let opts = AdfOptions {
+            table_max_cols: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_block_node(&node, 0, &opts).unwrap(),
+            "|  |  |\n| --- | --- |\n| 1 | 2 |\n\n_2 more columns not shown_"
+        );
+    }
+
+    #[test]
+    fn test_code_block_preserved_verbatim() {
+        let adf = json!({
+            "type": "doc",
+            "content": [{
+                "type": "codeBlock",
+                "attrs": {"language": "python"},
+                "content": [{"type": "text", "text": "def f():\n\n\n    return 1\n"}]
+            }]
+        });
+        assert_eq!(
+            crate::markdown::adf_to_markdown(&adf),
+            "```python\ndef f():\n\n\n    return 1\n```"
+        );
     }
 
     #[test]
@@ -469,7 +780,7 @@ mod tests {
                 {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Item 2"}]}]}
             ]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("- Item 1"));
         assert!(result.contains("- Item 2"));
     }
@@ -483,7 +794,7 @@ mod tests {
                 {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Second"}]}]}
             ]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("1. First"));
         assert!(result.contains("2. Second"));
     }
@@ -495,7 +806,7 @@ mod tests {
             "attrs": {"language": "rust"},
             "content": [{"type": "text", "text": "fn main() {}"}]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("```rust"));
         assert!(result.contains("fn main() {}"));
     }
@@ -506,14 +817,17 @@ mod tests {
             "type": "blockquote",
             "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Quote"}]}]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("> Quote"));
     }
 
     #[test]
     fn test_rule() {
         let node = json!({"type": "rule"});
-        assert_eq!(convert_block_node(&node, 0), Some("---".into()));
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("---".into())
+        );
     }
 
     #[test]
@@ -523,7 +837,7 @@ mod tests {
             "attrs": {"panelType": "info"},
             "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Note"}]}]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("> **INFO**"));
         assert!(result.contains("Note"));
     }
@@ -543,12 +857,105 @@ mod tests {
                 ]}
             ]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("| A | B |"));
         assert!(result.contains("| --- | --- |"));
         assert!(result.contains("| 1 | 2 |"));
     }
 
+    #[test]
+    fn test_table_without_header_row() {
+        let node = json!({
+            "type": "table",
+            "content": [
+                {"type": "tableRow", "content": [
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "1"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "2"}]}]}
+                ]},
+                {"type": "tableRow", "content": [
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "3"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "4"}]}]}
+                ]}
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(result, "|  |  |\n| --- | --- |\n| 1 | 2 |\n| 3 | 4 |");
+    }
+
+    #[test]
+    fn test_media_group_lists_every_file() {
+        let node = json!({
+            "type": "mediaGroup",
+            "content": [
+                {"type": "media", "attrs": {"type": "file", "id": "a1", "alt": "one.png"}},
+                {"type": "media", "attrs": {"type": "file", "id": "a2", "alt": "two.pdf"}},
+                {"type": "media", "attrs": {"type": "file", "id": "a3"}}
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(result, "[Media: one.png]\n[Media: two.pdf]\n[Media: a3]");
+    }
+
+    #[test]
+    fn test_media_single_ignores_caption() {
+        let node = json!({
+            "type": "mediaSingle",
+            "content": [
+                {"type": "media", "attrs": {"id": "a1", "alt": "shot.png"}},
+                {"type": "caption", "attrs": {}, "content": [{"type": "text", "text": "A screenshot"}]}
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(result, "[Media: shot.png]");
+    }
+
+    #[test]
+    fn test_table_column_alignment() {
+        let node = json!({
+            "type": "table",
+            "content": [
+                {"type": "tableRow", "content": [
+                    {"type": "tableHeader", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Name"}]}]},
+                    {"type": "tableHeader", "content": [{"type": "paragraph", "marks": [{"type": "alignment", "attrs": {"align": "center"}}], "content": [{"type": "text", "text": "Score"}]}]},
+                    {"type": "tableHeader", "attrs": {"align": "end"}, "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Total"}]}]}
+                ]},
+                {"type": "tableRow", "content": [
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "A"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "1"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "2"}]}]}
+                ]}
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            "| Name | Score | Total |\n| --- | :---: | ---: |\n| A | 1 | 2 |"
+        );
+    }
+
+    #[test]
+    fn test_table_with_left_header_column() {
+        let node = json!({
+            "type": "table",
+            "content": [
+                {"type": "tableRow", "content": [
+                    {"type": "tableHeader", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Name"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Alice"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Bob"}]}]}
+                ]},
+                {"type": "tableRow", "content": [
+                    {"type": "tableHeader", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Role"}]}]},
+                    {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Dev"}]}]}
+                ]}
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            "| Name | Alice | Bob |\n| --- | --- | --- |\n| Role | Dev |  |"
+        );
+    }
+
     #[test]
     fn test_task_list() {
         let node = json!({
@@ -558,7 +965,7 @@ mod tests {
                 {"type": "taskItem", "attrs": {"state": "DONE"}, "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Done"}]}]}
             ]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert!(result.contains("- [ ] Todo"));
         assert!(result.contains("- [x] Done"));
     }
@@ -570,18 +977,103 @@ mod tests {
             "attrs": {"title": "Click to expand"},
             "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Hidden content"}]}]
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let opts = AdfOptions {
+            expand_style: ExpandStyle::Bold,
+            ..Default::default()
+        };
+        let result = convert_block_node(&node, 0, &opts).unwrap();
         assert!(result.contains("**Click to expand**"));
         assert!(result.contains("Hidden content"));
     }
 
+    #[test]
+    fn test_layout_column_widths() {
+        let column = |width: f64, text: &str| {
+            json!({
+                "type": "layoutColumn",
+                "attrs": {"width": width},
+                "content": [{"type": "paragraph", "content": [{"type": "text", "text": text}]}]
+            })
+        };
+        let node = json!({
+            "type": "layoutSection",
+            "content": [column(70.0, "Main"), column(30.0, "Side")]
+        });
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()).unwrap(),
+            "<!-- column width=70% -->\nMain\n\n---\n\n<!-- column width=30% -->\nSide"
+        );
+    }
+
+    #[test]
+    fn test_expand_details_keeps_nesting() {
+        let node = json!({
+            "type": "expand",
+            "attrs": {"title": "Outer <1>"},
+            "content": [
+                {"type": "paragraph", "content": [{"type": "text", "text": "Intro"}]},
+                {
+                    "type": "nestedExpand",
+                    "attrs": {"title": "Inner"},
+                    "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Deep"}]}]
+                }
+            ]
+        });
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            "<details>\n<summary>Outer &lt;1&gt;</summary>\n\nIntro\n\n\
+             <details>\n<summary>Inner</summary>\n\nDeep\n\n</details>\n\n</details>"
+        );
+    }
+
+    #[test]
+    fn test_expand_nesting_is_depth_limited() {
+        let mut node = json!({"type": "paragraph", "content": [{"type": "text", "text": "x"}]});
+        for _ in 0..200 {
+            node = json!({"type": "nestedExpand", "content": [node]});
+        }
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert!(result.contains("[Content truncated: max depth exceeded]"));
+        assert_eq!(result.matches("<details>").count(), MAX_DEPTH + 1);
+    }
+
+    #[test]
+    fn test_list_inside_nested_expand_not_indented() {
+        let list = json!({
+            "type": "bulletList",
+            "content": [{"type": "listItem", "content": [
+                {"type": "paragraph", "content": [{"type": "text", "text": "a"}]},
+                {"type": "bulletList", "content": [{"type": "listItem", "content": [
+                    {"type": "paragraph", "content": [{"type": "text", "text": "b"}]}
+                ]}]}
+            ]}]
+        });
+        let node =
+            json!({"type": "expand", "content": [{"type": "nestedExpand", "content": [list]}]});
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
+        assert!(result.contains("\n\n- a \n  - b\n\n"), "{}", result);
+    }
+
+    #[test]
+    fn test_extension_placeholder() {
+        let node = json!({
+            "type": "extension",
+            "attrs": {"extensionType": "com.atlassian.confluence.macro.core", "extensionKey": "toc"}
+        });
+        assert_eq!(
+            convert_block_node(&node, 0, &AdfOptions::default()),
+            Some("[Macro: toc]".into())
+        );
+    }
+
     #[test]
     fn test_embed_card() {
         let node = json!({
             "type": "embedCard",
             "attrs": {"url": "https://example.com"}
         });
-        let result = convert_block_node(&node, 0).unwrap();
+        let result = convert_block_node(&node, 0, &AdfOptions::default()).unwrap();
         assert_eq!(result, "[https://example.com](https://example.com)");
     }
 }
@@ -1,28 +1,78 @@
+use super::AdfOptions;
 use super::marks::apply_marks;
+use crate::config::StatusStyle;
+use crate::markdown::common::escape_markdown;
 use serde_json::Value;
 
-pub fn convert_inline_nodes(nodes: &[Value]) -> String {
-    nodes.iter().map(convert_inline_node).collect()
+/// Where an inline run ends up. Headings and table cells must stay on one line, so a
+/// `hardBreak` there becomes a space instead of a newline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineContext {
+    Block,
+    SingleLine,
 }
 
-fn convert_inline_node(node: &Value) -> String {
+pub fn convert_inline_nodes(nodes: &[Value], ctx: InlineContext, opts: &AdfOptions) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < nodes.len() {
+        // Merge adjacent text runs with identical marks so `**foo**` + `**bar**`
+        // becomes `**foobar**` instead of the broken `**foo****bar**`
+        let run_end = text_run_end(nodes, i);
+        if run_end > i + 1 {
+            let text: String = nodes[i..run_end]
+                .iter()
+                .filter_map(|n| n.get("text").and_then(|t| t.as_str()))
+                .collect();
+            let mut merged = nodes[i].clone();
+            merged["text"] = Value::String(text);
+            result.push_str(&convert_text(&merged, opts));
+        } else {
+            result.push_str(&convert_inline_node(&nodes[i], ctx, opts));
+        }
+        i = run_end;
+    }
+
+    result
+}
+
+/// Index one past the last consecutive text node sharing the marks of `nodes[start]`
+fn text_run_end(nodes: &[Value], start: usize) -> usize {
+    let is_text = |n: &Value| n.get("type").and_then(|t| t.as_str()) == Some("text");
+    if !is_text(&nodes[start]) {
+        return start + 1;
+    }
+
+    let marks = nodes[start].get("marks");
+    nodes[start + 1..]
+        .iter()
+        .position(|n| !is_text(n) || n.get("marks") != marks)
+        .map_or(nodes.len(), |offset| start + 1 + offset)
+}
+
+fn convert_inline_node(node: &Value, ctx: InlineContext, opts: &AdfOptions) -> String {
     let node_type = node.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
     match node_type {
-        "text" => convert_text(node),
-        "hardBreak" => "\n".into(),
-        "mention" => convert_mention(node),
+        "text" => convert_text(node, opts),
+        "hardBreak" => match ctx {
+            InlineContext::Block => "\n".into(),
+            InlineContext::SingleLine => " ".into(),
+        },
+        "mention" => convert_mention(node, opts),
         "emoji" => convert_emoji(node),
         "inlineCard" => convert_inline_card(node),
-        "date" => convert_date(node),
-        "status" => convert_status(node),
+        "date" => convert_date(node, opts),
+        "status" => convert_status(node, opts),
         "mediaInline" => convert_media_inline(node),
         "placeholder" => convert_placeholder(node),
+        "inlineExtension" => convert_macro(node),
         _ => String::new(),
     }
 }
 
-fn convert_text(node: &Value) -> String {
+fn convert_text(node: &Value, opts: &AdfOptions) -> String {
     let text = node
         .get("text")
         .and_then(|t| t.as_str())
@@ -30,18 +80,39 @@ fn convert_text(node: &Value) -> String {
         .to_string();
 
     let marks = node.get("marks").and_then(|m| m.as_array());
-    apply_marks(text, marks)
+
+    // Code spans render their content literally, so escaping would show the backslashes
+    let is_code = marks.is_some_and(|marks| {
+        marks
+            .iter()
+            .any(|m| m.get("type").and_then(|t| t.as_str()) == Some("code"))
+    });
+    let text = if is_code {
+        text
+    } else {
+        escape_markdown(&text)
+    };
+
+    apply_marks(text, marks, opts.color_marks)
 }
 
-fn convert_mention(node: &Value) -> String {
+/// `@name`, or a link to the person's profile with `markdown.link_mentions`
+fn convert_mention(node: &Value, opts: &AdfOptions) -> String {
     let attrs = node.get("attrs");
+    let id = attrs.and_then(|a| a.get("id")).and_then(|i| i.as_str());
     let text = attrs
         .and_then(|a| a.get("text"))
         .and_then(|t| t.as_str())
-        .or_else(|| attrs.and_then(|a| a.get("id")).and_then(|i| i.as_str()))
+        .or(id)
         .unwrap_or("user");
+    let mention = format!("@{}", text.trim_start_matches('@'));
 
-    format!("@{}", text.trim_start_matches('@'))
+    match (&opts.mention_base_url, id) {
+        (Some(base), Some(id)) if !id.is_empty() => {
+            format!("[{}]({}/jira/people/{})", mention, base, id)
+        }
+        _ => mention,
+    }
 }
 
 fn convert_emoji(node: &Value) -> String {
@@ -72,7 +143,7 @@ fn convert_inline_card(node: &Value) -> String {
     }
 }
 
-fn convert_date(node: &Value) -> String {
+fn convert_date(node: &Value, opts: &AdfOptions) -> String {
     let timestamp = node
         .get("attrs")
         .and_then(|a| a.get("timestamp"))
@@ -83,69 +154,24 @@ fn convert_date(node: &Value) -> String {
         return String::new();
     }
 
-    if let Ok(ts) = timestamp.parse::<i64>() {
-        let secs = ts / 1000;
-        let date = chrono_format_date(secs);
-        return date;
-    }
-
-    timestamp.to_string()
-}
-
-fn chrono_format_date(secs: i64) -> String {
-    // Handle negative timestamps (pre-1970) by returning raw timestamp
-    if secs < 0 {
-        return format!("1970-01-01 (pre-epoch: {})", secs);
-    }
-
-    // Validate reasonable date range (1970 to 3000)
-    const MAX_SECS: i64 = 32503680000; // Year 3000 approx
-    if secs > MAX_SECS {
-        return format!("(invalid timestamp: {})", secs);
-    }
-
-    let days_since_epoch = secs / 86400;
-    let mut year = 1970i32;
-    let mut remaining_days = days_since_epoch;
-
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    let days_in_months: [i64; 12] = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1u32;
-    for days in days_in_months {
-        if remaining_days < days {
-            break;
-        }
-        remaining_days -= days;
-        month += 1;
-    }
-
-    let day = remaining_days + 1;
-    format!("{:04}-{:02}-{:02}", year, month, day)
-}
-
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+    timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|ms| opts.timezone.format_millis(ms))
+        .unwrap_or_else(|| timestamp.to_string())
 }
 
-fn convert_status(node: &Value) -> String {
+fn convert_status(node: &Value, opts: &AdfOptions) -> String {
     let attrs = node.get("attrs");
     let text = attrs
         .and_then(|a| a.get("text"))
         .and_then(|t| t.as_str())
         .unwrap_or("status");
+
+    if opts.status_style == StatusStyle::Badge {
+        return format!("`{}`", text.to_uppercase());
+    }
+
     let color = attrs
         .and_then(|a| a.get("color"))
         .and_then(|c| c.as_str())
@@ -184,6 +210,18 @@ fn convert_placeholder(node: &Value) -> String {
     format!("{{{}}}", text)
 }
 
+/// Placeholder for macros (`inlineExtension`, body-less `extension`) that have no
+/// markdown equivalent, so they stay visible instead of being dropped
+pub fn convert_macro(node: &Value) -> String {
+    let attrs = node.get("attrs");
+    let name = ["extensionKey", "extensionType"]
+        .iter()
+        .find_map(|k| attrs.and_then(|a| a.get(*k)).and_then(|v| v.as_str()))
+        .unwrap_or("unknown");
+
+    format!("[Macro: {}]", name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,7 +230,10 @@ mod tests {
     #[test]
     fn test_text() {
         let node = json!({"type": "text", "text": "hello"});
-        assert_eq!(convert_inline_node(&node), "hello");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "hello"
+        );
     }
 
     #[test]
@@ -202,70 +243,234 @@ mod tests {
             "text": "bold",
             "marks": [{"type": "strong"}]
         });
-        assert_eq!(convert_inline_node(&node), "**bold**");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "**bold**"
+        );
+    }
+
+    #[test]
+    fn test_text_escapes_markdown() {
+        let node = json!({"type": "text", "text": "C_code *x*"});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "C\\_code \\*x\\*"
+        );
+    }
+
+    #[test]
+    fn test_code_mark_not_escaped() {
+        let node = json!({
+            "type": "text",
+            "text": "C_code",
+            "marks": [{"type": "code"}]
+        });
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "`C_code`"
+        );
+    }
+
+    #[test]
+    fn test_adjacent_strong_runs_merged() {
+        let nodes = vec![
+            json!({"type": "text", "text": "foo", "marks": [{"type": "strong"}]}),
+            json!({"type": "text", "text": "bar", "marks": [{"type": "strong"}]}),
+        ];
+        assert_eq!(
+            convert_inline_nodes(&nodes, InlineContext::Block, &AdfOptions::default()),
+            "**foobar**"
+        );
+    }
+
+    #[test]
+    fn test_runs_with_different_marks_not_merged() {
+        let nodes = vec![
+            json!({"type": "text", "text": "foo", "marks": [{"type": "strong"}]}),
+            json!({"type": "text", "text": "bar", "marks": [{"type": "em"}]}),
+            json!({"type": "text", "text": " baz"}),
+        ];
+        assert_eq!(
+            convert_inline_nodes(&nodes, InlineContext::Block, &AdfOptions::default()),
+            "**foo***bar* baz"
+        );
     }
 
     #[test]
     fn test_hard_break() {
         let node = json!({"type": "hardBreak"});
-        assert_eq!(convert_inline_node(&node), "\n");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "\n"
+        );
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::SingleLine, &AdfOptions::default()),
+            " "
+        );
     }
 
     #[test]
     fn test_mention() {
         let node = json!({"type": "mention", "attrs": {"text": "@john"}});
-        assert_eq!(convert_inline_node(&node), "@john");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "@john"
+        );
     }
 
     #[test]
     fn test_mention_with_id() {
         let node = json!({"type": "mention", "attrs": {"id": "user123"}});
-        assert_eq!(convert_inline_node(&node), "@user123");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "@user123"
+        );
+    }
+
+    #[test]
+    fn test_mention_link() {
+        let opts = AdfOptions {
+            mention_base_url: Some("https://test.atlassian.net".into()),
+            ..Default::default()
+        };
+        let node = json!({"type": "mention", "attrs": {"id": "5b10ac8d82e05b22cc7d4ef5", "text": "@Jane Doe"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &opts),
+            "[@Jane Doe](https://test.atlassian.net/jira/people/5b10ac8d82e05b22cc7d4ef5)"
+        );
+
+        // Without an accountId there is nothing to link to
+        let node = json!({"type": "mention", "attrs": {"text": "@john"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &opts),
+            "@john"
+        );
     }
 
     #[test]
     fn test_emoji() {
         let node = json!({"type": "emoji", "attrs": {"shortName": ":smile:", "text": "😄"}});
-        assert_eq!(convert_inline_node(&node), "😄");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "😄"
+        );
     }
 
     #[test]
     fn test_inline_card() {
         let node = json!({"type": "inlineCard", "attrs": {"url": "https://example.com"}});
         assert_eq!(
-            convert_inline_node(&node),
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
             "[https://example.com](https://example.com)"
         );
     }
 
+    fn legacy_status() -> AdfOptions {
+        AdfOptions {
+            status_style: StatusStyle::Legacy,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_date() {
         let node = json!({"type": "date", "attrs": {"timestamp": "1704067200000"}}); // 2024-01-01
-        assert_eq!(convert_inline_node(&node), "2024-01-01");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "2024-01-01"
+        );
+    }
+
+    #[test]
+    fn test_date_with_time_of_day() {
+        // 2024-01-01T13:30:00Z
+        let node = json!({"type": "date", "attrs": {"timestamp": "1704115800000"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "2024-01-01 13:30"
+        );
+    }
+
+    #[test]
+    fn test_date_with_fixed_offset() {
+        let opts = AdfOptions {
+            timezone: "+09:00".parse().unwrap(),
+            ..Default::default()
+        };
+        // 2024-01-01T13:30:00Z is 22:30 in UTC+9
+        let node = json!({"type": "date", "attrs": {"timestamp": "1704115800000"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &opts),
+            "2024-01-01 22:30"
+        );
+    }
+
+    #[test]
+    fn test_calendar_date_not_shifted_by_negative_offset() {
+        let opts = AdfOptions {
+            timezone: "-05:00".parse().unwrap(),
+            ..Default::default()
+        };
+        // 2024-01-01T00:00:00Z is a calendar date, not 2023-12-31 19:00
+        let node = json!({"type": "date", "attrs": {"timestamp": "1704067200000"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &opts),
+            "2024-01-01"
+        );
+    }
+
+    #[test]
+    fn test_date_pre_epoch() {
+        let node = json!({"type": "date", "attrs": {"timestamp": "-86400000"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "1969-12-31"
+        );
+    }
+
+    #[test]
+    fn test_status_badge() {
+        let node = json!({"type": "status", "attrs": {"text": "done", "color": "green"}});
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "`DONE`"
+        );
     }
 
     #[test]
     fn test_status_green() {
         let node = json!({"type": "status", "attrs": {"text": "done", "color": "green"}});
-        assert_eq!(convert_inline_node(&node), "[OK] DONE");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &legacy_status()),
+            "[OK] DONE"
+        );
     }
 
     #[test]
     fn test_status_red() {
         let node = json!({"type": "status", "attrs": {"text": "failed", "color": "red"}});
-        assert_eq!(convert_inline_node(&node), "[ERR] FAILED");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &legacy_status()),
+            "[ERR] FAILED"
+        );
     }
 
     #[test]
     fn test_media_inline() {
         let node = json!({"type": "mediaInline", "attrs": {"alt": "screenshot.png"}});
-        assert_eq!(convert_inline_node(&node), "[Media: screenshot.png]");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "[Media: screenshot.png]"
+        );
     }
 
     #[test]
     fn test_placeholder() {
         let node = json!({"type": "placeholder", "attrs": {"text": "Enter name"}});
-        assert_eq!(convert_inline_node(&node), "{Enter name}");
+        assert_eq!(
+            convert_inline_node(&node, InlineContext::Block, &AdfOptions::default()),
+            "{Enter name}"
+        );
     }
 
     #[test]
@@ -274,6 +479,29 @@ mod tests {
             json!({"type": "text", "text": "Hello "}),
             json!({"type": "text", "text": "world", "marks": [{"type": "strong"}]}),
         ];
-        assert_eq!(convert_inline_nodes(&nodes), "Hello **world**");
+        assert_eq!(
+            convert_inline_nodes(&nodes, InlineContext::Block, &AdfOptions::default()),
+            "Hello **world**"
+        );
+    }
+
+    #[test]
+    fn test_inline_extension_placeholder() {
+        let nodes = vec![
+            json!({"type": "text", "text": "See "}),
+            json!({
+                "type": "inlineExtension",
+                "attrs": {
+                    "extensionType": "com.atlassian.confluence.macro.core",
+                    "extensionKey": "anchor",
+                    "parameters": {}
+                }
+            }),
+            json!({"type": "inlineExtension", "attrs": {"extensionType": "com.example"}}),
+        ];
+        assert_eq!(
+            convert_inline_nodes(&nodes, InlineContext::Block, &AdfOptions::default()),
+            "See [Macro: anchor][Macro: com.example]"
+        );
     }
 }
@@ -1,6 +1,7 @@
+use crate::config::ColorMarks;
 use serde_json::Value;
 
-pub fn apply_marks(text: String, marks: Option<&Vec<Value>>) -> String {
+pub fn apply_marks(text: String, marks: Option<&Vec<Value>>, color_marks: ColorMarks) -> String {
     let Some(marks) = marks else {
         return text;
     };
@@ -18,8 +19,8 @@ pub fn apply_marks(text: String, marks: Option<&Vec<Value>>) -> String {
             "underline" => format!("<u>{}</u>", result),
             "link" => format_link(&result, attrs),
             "subsup" => format_subsup(&result, attrs),
-            "textColor" => format_text_color(&result, attrs),
-            "backgroundColor" => format_background_color(&result, attrs),
+            "textColor" => format_text_color(&result, attrs, color_marks),
+            "backgroundColor" => format_background_color(&result, attrs, color_marks),
             _ => result,
         };
     }
@@ -63,29 +64,31 @@ fn format_subsup(text: &str, attrs: Option<&Value>) -> String {
     }
 }
 
-fn format_text_color(text: &str, attrs: Option<&Value>) -> String {
+fn format_text_color(text: &str, attrs: Option<&Value>, style: ColorMarks) -> String {
     let color = attrs
         .and_then(|a| a.get("color"))
         .and_then(|c| c.as_str())
         .unwrap_or("");
 
-    if color.is_empty() {
-        text.to_string()
-    } else {
-        format!("<span style=\"color:{}\">{}</span>", color, text)
+    match style {
+        _ if color.is_empty() => text.to_string(),
+        ColorMarks::Html => format!("<span style=\"color:{}\">{}</span>", color, text),
+        ColorMarks::Drop => text.to_string(),
+        ColorMarks::Bracket => format!("[color:{}]{}", color, text),
     }
 }
 
-fn format_background_color(text: &str, attrs: Option<&Value>) -> String {
+fn format_background_color(text: &str, attrs: Option<&Value>, style: ColorMarks) -> String {
     let color = attrs
         .and_then(|a| a.get("color"))
         .and_then(|c| c.as_str())
         .unwrap_or("");
 
-    if color.is_empty() {
-        text.to_string()
-    } else {
-        format!("<mark style=\"background:{}\">{}</mark>", color, text)
+    match style {
+        _ if color.is_empty() => text.to_string(),
+        ColorMarks::Html => format!("<mark style=\"background:{}\">{}</mark>", color, text),
+        ColorMarks::Drop => text.to_string(),
+        ColorMarks::Bracket => format!("[background:{}]{}", color, text),
     }
 }
 
@@ -96,44 +99,59 @@ mod tests {
 
     #[test]
     fn test_no_marks() {
-        assert_eq!(apply_marks("text".into(), None), "text");
+        assert_eq!(apply_marks("text".into(), None, ColorMarks::Html), "text");
     }
 
     #[test]
     fn test_strong() {
         let marks = vec![json!({"type": "strong"})];
-        assert_eq!(apply_marks("bold".into(), Some(&marks)), "**bold**");
+        assert_eq!(
+            apply_marks("bold".into(), Some(&marks), ColorMarks::Html),
+            "**bold**"
+        );
     }
 
     #[test]
     fn test_em() {
         let marks = vec![json!({"type": "em"})];
-        assert_eq!(apply_marks("italic".into(), Some(&marks)), "*italic*");
+        assert_eq!(
+            apply_marks("italic".into(), Some(&marks), ColorMarks::Html),
+            "*italic*"
+        );
     }
 
     #[test]
     fn test_code() {
         let marks = vec![json!({"type": "code"})];
-        assert_eq!(apply_marks("code".into(), Some(&marks)), "`code`");
+        assert_eq!(
+            apply_marks("code".into(), Some(&marks), ColorMarks::Html),
+            "`code`"
+        );
     }
 
     #[test]
     fn test_strike() {
         let marks = vec![json!({"type": "strike"})];
-        assert_eq!(apply_marks("strike".into(), Some(&marks)), "~~strike~~");
+        assert_eq!(
+            apply_marks("strike".into(), Some(&marks), ColorMarks::Html),
+            "~~strike~~"
+        );
     }
 
     #[test]
     fn test_underline() {
         let marks = vec![json!({"type": "underline"})];
-        assert_eq!(apply_marks("under".into(), Some(&marks)), "<u>under</u>");
+        assert_eq!(
+            apply_marks("under".into(), Some(&marks), ColorMarks::Html),
+            "<u>under</u>"
+        );
     }
 
     #[test]
     fn test_link() {
         let marks = vec![json!({"type": "link", "attrs": {"href": "https://example.com"}})];
         assert_eq!(
-            apply_marks("click".into(), Some(&marks)),
+            apply_marks("click".into(), Some(&marks), ColorMarks::Html),
             "[click](https://example.com)"
         );
     }
@@ -144,7 +162,7 @@ mod tests {
             json!({"type": "link", "attrs": {"href": "https://example.com", "title": "Example"}}),
         ];
         assert_eq!(
-            apply_marks("click".into(), Some(&marks)),
+            apply_marks("click".into(), Some(&marks), ColorMarks::Html),
             "[click](https://example.com \"Example\")"
         );
     }
@@ -152,20 +170,26 @@ mod tests {
     #[test]
     fn test_subsup_sub() {
         let marks = vec![json!({"type": "subsup", "attrs": {"type": "sub"}})];
-        assert_eq!(apply_marks("2".into(), Some(&marks)), "<sub>2</sub>");
+        assert_eq!(
+            apply_marks("2".into(), Some(&marks), ColorMarks::Html),
+            "<sub>2</sub>"
+        );
     }
 
     #[test]
     fn test_subsup_sup() {
         let marks = vec![json!({"type": "subsup", "attrs": {"type": "sup"}})];
-        assert_eq!(apply_marks("2".into(), Some(&marks)), "<sup>2</sup>");
+        assert_eq!(
+            apply_marks("2".into(), Some(&marks), ColorMarks::Html),
+            "<sup>2</sup>"
+        );
     }
 
     #[test]
     fn test_text_color() {
         let marks = vec![json!({"type": "textColor", "attrs": {"color": "#ff0000"}})];
         assert_eq!(
-            apply_marks("red".into(), Some(&marks)),
+            apply_marks("red".into(), Some(&marks), ColorMarks::Html),
             "<span style=\"color:#ff0000\">red</span>"
         );
     }
@@ -174,7 +198,7 @@ mod tests {
     fn test_background_color() {
         let marks = vec![json!({"type": "backgroundColor", "attrs": {"color": "#ffff00"}})];
         assert_eq!(
-            apply_marks("highlight".into(), Some(&marks)),
+            apply_marks("highlight".into(), Some(&marks), ColorMarks::Html),
             "<mark style=\"background:#ffff00\">highlight</mark>"
         );
     }
@@ -182,6 +206,36 @@ mod tests {
     #[test]
     fn test_multiple_marks() {
         let marks = vec![json!({"type": "strong"}), json!({"type": "em"})];
-        assert_eq!(apply_marks("text".into(), Some(&marks)), "***text***");
+        assert_eq!(
+            apply_marks("text".into(), Some(&marks), ColorMarks::Html),
+            "***text***"
+        );
+    }
+
+    #[test]
+    fn test_color_marks_drop() {
+        let marks = vec![
+            json!({"type": "textColor", "attrs": {"color": "#ff0000"}}),
+            json!({"type": "backgroundColor", "attrs": {"color": "#ffff00"}}),
+        ];
+        assert_eq!(
+            apply_marks("plain".into(), Some(&marks), ColorMarks::Drop),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_color_marks_bracket() {
+        let marks = vec![json!({"type": "textColor", "attrs": {"color": "red"}})];
+        assert_eq!(
+            apply_marks("alert".into(), Some(&marks), ColorMarks::Bracket),
+            "[color:red]alert"
+        );
+
+        let marks = vec![json!({"type": "backgroundColor", "attrs": {"color": "#ffff00"}})];
+        assert_eq!(
+            apply_marks("note".into(), Some(&marks), ColorMarks::Bracket),
+            "[background:#ffff00]note"
+        );
     }
 }
@@ -2,17 +2,116 @@ mod blocks;
 mod inline;
 mod marks;
 
+use crate::config::{ColorMarks, Config, ExpandStyle, StatusStyle};
 use crate::markdown::common::normalize_whitespace;
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, TimeZone, Utc};
 use serde_json::Value;
 
+/// Rendering options for ADF → markdown conversion, built from `[markdown]` config
+#[derive(Debug, Clone, Default)]
+pub struct AdfOptions {
+    pub timezone: DateTimezone,
+    pub status_style: StatusStyle,
+    pub color_marks: ColorMarks,
+    pub expand_style: ExpandStyle,
+    pub heading_offset: u8,
+    pub cell_max_chars: Option<usize>,
+    pub table_max_cols: Option<usize>,
+    /// Site URL used to link mentions to profiles (`markdown.link_mentions`)
+    pub mention_base_url: Option<String>,
+}
+
+impl AdfOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            // Config::validate rejects unknown timezones, so falling back is safe
+            timezone: config
+                .markdown
+                .timezone
+                .as_deref()
+                .and_then(|tz| tz.parse().ok())
+                .unwrap_or_default(),
+            status_style: config.markdown.status_style.unwrap_or_default(),
+            color_marks: config.markdown.color_marks.unwrap_or_default(),
+            expand_style: config.markdown.expand_style.unwrap_or_default(),
+            heading_offset: config.markdown.heading_offset.unwrap_or(0),
+            cell_max_chars: config.markdown.cell_max_chars,
+            table_max_cols: config.markdown.table_max_cols,
+            // Data Center mention ids are user keys and it has no /jira/people profiles
+            mention_base_url: (config.markdown.link_mentions.unwrap_or(false)
+                && !config.is_datacenter())
+            .then(|| config.base_url().to_string()),
+        }
+    }
+}
+
+/// Timezone used to render ADF `date` nodes that carry a time of day. Plain dates (UTC
+/// midnight) are calendar dates and render unshifted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateTimezone {
+    #[default]
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl std::str::FromStr for DateTimezone {
+    type Err = anyhow::Error;
+
+    /// Accepts "UTC", "local", or a fixed offset such as "+09:00"
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim() {
+            tz if tz.eq_ignore_ascii_case("utc") || tz == "Z" => Ok(Self::Utc),
+            tz if tz.eq_ignore_ascii_case("local") => Ok(Self::Local),
+            tz => tz.parse::<FixedOffset>().map(Self::Fixed).map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid timezone '{}': expected UTC, local, or an offset like +09:00",
+                    tz
+                )
+            }),
+        }
+    }
+}
+
+impl DateTimezone {
+    /// Format epoch milliseconds as `YYYY-MM-DD`, or as `YYYY-MM-DD HH:MM` in this
+    /// timezone when not at UTC midnight
+    fn format_millis(&self, ms: i64) -> Option<String> {
+        let utc = DateTime::<Utc>::from_timestamp_millis(ms)?;
+        if utc.time() == NaiveTime::MIN {
+            return Some(utc.format("%Y-%m-%d").to_string());
+        }
+        Some(match self {
+            Self::Utc => format_date_time(&utc),
+            Self::Local => format_date_time(&utc.with_timezone(&Local)),
+            Self::Fixed(offset) => format_date_time(&utc.with_timezone(offset)),
+        })
+    }
+}
+
+fn format_date_time<Tz: TimeZone>(dt: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if dt.time() == NaiveTime::MIN {
+        dt.format("%Y-%m-%d").to_string()
+    } else {
+        dt.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
 pub fn adf_to_markdown(adf: &Value) -> String {
+    adf_to_markdown_with(adf, &AdfOptions::default())
+}
+
+pub fn adf_to_markdown_with(adf: &Value, opts: &AdfOptions) -> String {
     let Some(content) = adf.get("content").and_then(|c| c.as_array()) else {
         return String::new();
     };
 
     let blocks: Vec<String> = content
         .iter()
-        .filter_map(|node| blocks::convert_block_node(node, 0))
+        .filter_map(|node| blocks::convert_block_node(node, 0, opts))
         .collect();
 
     normalize_whitespace(&blocks.join("\n\n"))
@@ -23,6 +122,19 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_mention_links_cloud_only() {
+        let mut config = crate::test_utils::create_test_config();
+        config.markdown.link_mentions = Some(true);
+        assert_eq!(
+            AdfOptions::from_config(&config).mention_base_url.as_deref(),
+            Some("https://test.atlassian.net")
+        );
+
+        config.deployment = crate::Deployment::DataCenter;
+        assert_eq!(AdfOptions::from_config(&config).mention_base_url, None);
+    }
+
     #[test]
     fn test_empty_doc() {
         let adf = json!({"type": "doc", "version": 1, "content": []});
@@ -115,6 +227,19 @@ mod tests {
         assert_eq!(adf_to_markdown(&adf), "[click here](https://example.com)");
     }
 
+    #[test]
+    fn test_literal_hash_not_heading() {
+        let adf = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{
+                "type": "paragraph",
+                "content": [{"type": "text", "text": "# not a heading"}]
+            }]
+        });
+        assert_eq!(adf_to_markdown(&adf), "\\# not a heading");
+    }
+
     #[test]
     fn test_table() {
         let adf = json!({
@@ -1,8 +1,25 @@
+/// Collapse runs of blank lines and trim leading/trailing blank lines.
+/// Lines inside fenced code blocks are kept verbatim.
 pub fn normalize_whitespace(text: &str) -> String {
     let mut lines: Vec<&str> = Vec::new();
     let mut prev_empty = false;
+    let mut fence: Option<&str> = None;
 
     for line in text.lines() {
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|m| line.trim_start().starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => fence = Some(m),
+            (Some(open), Some(m)) if open == m => fence = None,
+            (Some(_), _) => {
+                lines.push(line);
+                prev_empty = false;
+                continue;
+            }
+            _ => {}
+        }
+
         let is_empty = line.trim().is_empty();
         if is_empty {
             if !prev_empty {
@@ -33,6 +50,25 @@ pub fn normalize_whitespace(text: &str) -> String {
     lines[start..end].join("\n")
 }
 
+/// Backslash-escape characters that would otherwise be read as markdown syntax.
+/// `#` is only escaped at the start of a line, where it would begin a heading.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut at_line_start = true;
+
+    for c in text.chars() {
+        match c {
+            '\\' | '*' | '_' | '`' | '[' | ']' => escaped.push('\\'),
+            '#' if at_line_start => escaped.push('\\'),
+            _ => {}
+        }
+        escaped.push(c);
+        at_line_start = c == '\n';
+    }
+
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,4 +79,23 @@ mod tests {
         assert_eq!(normalize_whitespace("\n\na\n\n"), "a");
         assert_eq!(normalize_whitespace("a\nb\nc"), "a\nb\nc");
     }
+
+    #[test]
+    fn test_normalize_whitespace_keeps_fenced_code() {
+        let text = "intro\n\n\n```py\ndef f():\n\n\n    return 1\n  \n```\n\n\nafter";
+        assert_eq!(
+            normalize_whitespace(text),
+            "intro\n\n```py\ndef f():\n\n\n    return 1\n  \n```\n\nafter"
+        );
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("C_code"), "C\\_code");
+        assert_eq!(escape_markdown("# not a heading"), "\\# not a heading");
+        assert_eq!(escape_markdown("issue #12"), "issue #12");
+        assert_eq!(escape_markdown("a\n#b"), "a\n\\#b");
+        assert_eq!(escape_markdown("*[x]* `y`"), "\\*\\[x\\]\\* \\`y\\`");
+        assert_eq!(escape_markdown("plain text (ok)"), "plain text (ok)");
+    }
 }
@@ -1,10 +1,13 @@
 mod cleanup;
 mod elements;
 mod macros;
+mod to_text;
 
 use crate::markdown::common::normalize_whitespace;
 use htmd::HtmlToMarkdown;
 
+pub use to_text::confluence_to_text;
+
 pub fn confluence_to_markdown(html: &str) -> String {
     // 1. Pre-process: Process Confluence elements first (needs full metadata)
     let processed = elements::clean_confluence_html(html);
@@ -0,0 +1,226 @@
+use super::cleanup;
+use crate::markdown::common::normalize_whitespace;
+
+/// Elements whose content is never prose (macro parameters, scripts)
+const SKIPPED_TAGS: &[&str] = &["script", "style", "noscript", "ac:parameter"];
+
+/// Elements that start a new line in the extracted text
+const LINE_TAGS: &[&str] = &["br", "hr", "li", "tr", "ac:task"];
+
+/// Elements set off from their surroundings by a blank line
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "table",
+    "blockquote",
+    "pre",
+    "ac:structured-macro",
+];
+
+/// Convert Confluence storage HTML to plain text for indexing: metadata and binary
+/// residue are dropped, tags stripped, entities decoded, and whitespace collapsed.
+pub fn confluence_to_text(html: &str) -> String {
+    let cleaned = cleanup::clean_binary_data(&cleanup::clean_metadata(html));
+    let stripped = strip_tags(&cleaned);
+
+    let lines: Vec<String> = stripped
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    normalize_whitespace(&lines.join("\n"))
+}
+
+/// Single pass over the markup, emitting text nodes and line breaks for block elements
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut skip_depth = 0usize;
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        if skip_depth == 0 {
+            out.push_str(&decode_entities(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = tag_end(rest) else {
+            // Unterminated tag: treat the remainder as text
+            if skip_depth == 0 {
+                out.push_str(&decode_entities(rest));
+            }
+            rest = "";
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let self_closing = tag.ends_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if SKIPPED_TAGS.contains(&name.as_str()) {
+            if closing {
+                skip_depth = skip_depth.saturating_sub(1);
+            } else if !self_closing {
+                skip_depth += 1;
+            }
+            continue;
+        }
+
+        if skip_depth > 0 {
+            continue;
+        }
+
+        if BLOCK_TAGS.contains(&name.as_str()) || (LINE_TAGS.contains(&name.as_str()) && !closing) {
+            out.push('\n');
+        } else if matches!(name.as_str(), "td" | "th") && !closing {
+            out.push(' ');
+        }
+    }
+
+    if skip_depth == 0 {
+        out.push_str(&decode_entities(rest));
+    }
+    out
+}
+
+/// Index of the `>` closing the tag at the start of `s`, ignoring `>` inside quoted attributes
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&semi| semi <= 10)
+            .and_then(|semi| decode_entity(&rest[1..semi + 1]).map(|c| (c, semi + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        "ndash" => Some('–'),
+        "mdash" => Some('—'),
+        "hellip" => Some('…'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_tags_and_keeps_paragraphs() {
+        let html = "<h1>Title</h1><p>Hello <strong>world</strong></p><p>Second</p>";
+        assert_eq!(confluence_to_text(html), "Title\n\nHello world\n\nSecond");
+    }
+
+    #[test]
+    fn test_decodes_entities() {
+        let html = "<p>Tom &amp; Jerry &lt;3 &#39;quoted&#x27; &unknown;</p>";
+        assert_eq!(
+            confluence_to_text(html),
+            "Tom & Jerry <3 'quoted' &unknown;"
+        );
+    }
+
+    #[test]
+    fn test_skips_macro_parameters_and_keeps_body() {
+        let html = r#"<ac:structured-macro ac:name="code" ac:macro-id="abc">
+            <ac:parameter ac:name="language">rust</ac:parameter>
+            <ac:plain-text-body><![CDATA[let x = 1;]]></ac:plain-text-body>
+        </ac:structured-macro>"#;
+        let result = confluence_to_text(html);
+        assert_eq!(result, "let x = 1;");
+    }
+
+    #[test]
+    fn test_removes_binary_residue() {
+        let html = format!(
+            "<p>Before</p><mxGraphModel><root/></mxGraphModel><p>{}</p><p>After</p>",
+            "A".repeat(600)
+        );
+        assert_eq!(confluence_to_text(&html), "Before\n\nAfter");
+    }
+
+    #[test]
+    fn test_table_cells_are_space_separated() {
+        let html = "<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>";
+        assert_eq!(confluence_to_text(html), "A B\n1 2");
+    }
+
+    #[test]
+    fn test_list_items_on_separate_lines() {
+        let html = "<ul><li>One</li><li>Two</li></ul>";
+        assert_eq!(confluence_to_text(html), "One\nTwo");
+    }
+
+    #[test]
+    fn test_quoted_gt_in_attribute() {
+        let html = r#"<a title="a > b" href="x">link</a> text"#;
+        assert_eq!(confluence_to_text(html), "link text");
+    }
+}
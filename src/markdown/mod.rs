@@ -2,5 +2,5 @@ pub mod adf;
 mod common;
 pub mod confluence;
 
-pub use adf::adf_to_markdown;
-pub use confluence::confluence_to_markdown;
+pub use adf::{AdfOptions, adf_to_markdown, adf_to_markdown_with};
+pub use confluence::{confluence_to_markdown, confluence_to_text};
@@ -0,0 +1,248 @@
+//! Human-readable table rendering for `--output table`.
+//! Coloring lives here only; JSON and YAML output are never colored.
+
+use serde_json::{Map, Value};
+use std::io::IsTerminal;
+
+const MAX_CELL_WIDTH: usize = 60;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Color only when not disabled by `--no-color` or `NO_COLOR` and stdout is a terminal
+pub fn use_color(no_color_flag: bool) -> bool {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color_flag && !no_color_env && std::io::stdout().is_terminal()
+}
+
+/// Render a command result as an aligned table. The first array of objects found
+/// (the value itself, or a top-level field such as `items`) becomes the rows;
+/// any other value is rendered as a single row.
+pub fn render_table(value: &Value, color: bool) -> String {
+    match rows_of(value) {
+        Some(rows) => render_rows(rows, color),
+        None => render_rows(std::slice::from_ref(value), color),
+    }
+}
+
+fn rows_of(value: &Value) -> Option<&[Value]> {
+    let is_rows = |v: &Value| {
+        v.as_array()
+            .is_some_and(|a| !a.is_empty() && a.iter().all(Value::is_object))
+    };
+    if is_rows(value) {
+        return value.as_array().map(Vec::as_slice);
+    }
+    value
+        .as_object()?
+        .values()
+        .find(|v| is_rows(v))
+        .and_then(|v| v.as_array().map(Vec::as_slice))
+}
+
+fn render_rows(rows: &[Value], color: bool) -> String {
+    let flattened: Vec<Vec<(String, String)>> = rows
+        .iter()
+        .map(|row| row.as_object().map(flatten_row).unwrap_or_default())
+        .collect();
+
+    let mut columns: Vec<String> = Vec::new();
+    for row in &flattened {
+        for (name, _) in row {
+            if !columns.contains(name) {
+                columns.push(name.clone());
+            }
+        }
+    }
+    if columns.is_empty() {
+        return String::new();
+    }
+
+    let cells: Vec<Vec<String>> = flattened
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|col| {
+                    row.iter()
+                        .find(|(name, _)| name == col)
+                        .map(|(_, v)| truncate(v))
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([col.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    let header: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(col, w)| pad(&col.to_uppercase(), *w))
+        .collect();
+    out.push_str(header.join("  ").trim_end());
+    out.push('\n');
+
+    for row in &cells {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&columns)
+            .zip(&widths)
+            .map(|((cell, col), w)| {
+                let padded = pad(cell, *w);
+                match color.then(|| color_for(col, cell)).flatten() {
+                    // Pad before coloring so escape codes don't skew alignment
+                    Some(code) => format!("{}{}{}", code, padded, RESET),
+                    None => padded,
+                }
+            })
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Scalar columns of a row; Jira's nested `fields` object is flattened into the row
+fn flatten_row(row: &Map<String, Value>) -> Vec<(String, String)> {
+    let mut cells = Vec::new();
+    for (name, value) in row {
+        if name == "fields"
+            && let Some(fields) = value.as_object()
+        {
+            cells.extend(
+                fields
+                    .iter()
+                    .filter_map(|(n, v)| display_value(v).map(|d| (n.clone(), d))),
+            );
+        } else if let Some(display) = display_value(value) {
+            cells.push((name.clone(), display));
+        }
+    }
+    cells
+}
+
+/// Short display form: scalars as-is, objects by their name-like field, arrays joined
+fn display_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.replace(['\n', '\r', '\t'], " ")),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Object(obj) => ["name", "displayName", "value", "key", "title"]
+            .iter()
+            .find_map(|k| obj.get(*k).and_then(display_value)),
+        Value::Array(items) => {
+            let parts: Vec<String> = items.iter().filter_map(display_value).collect();
+            (!parts.is_empty()).then(|| parts.join(", "))
+        }
+    }
+}
+
+fn color_for(column: &str, value: &str) -> Option<&'static str> {
+    let value = value.to_lowercase();
+    match column.to_lowercase().as_str() {
+        "status" => {
+            if ["done", "closed", "resolved", "complete"]
+                .iter()
+                .any(|s| value.contains(s))
+            {
+                Some(GREEN)
+            } else if ["block", "reject", "fail", "cancel"]
+                .iter()
+                .any(|s| value.contains(s))
+            {
+                Some(RED)
+            } else if ["progress", "review", "testing"]
+                .iter()
+                .any(|s| value.contains(s))
+            {
+                Some(YELLOW)
+            } else {
+                None
+            }
+        }
+        "priority" => match value.as_str() {
+            "highest" | "high" | "blocker" | "critical" => Some(RED),
+            "medium" | "major" => Some(YELLOW),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_CELL_WIDTH {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+    cut.push('…');
+    cut
+}
+
+fn pad(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    format!("{}{}", text, " ".repeat(width.saturating_sub(len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn issues() -> Value {
+        json!({
+            "items": [
+                {"key": "PROJ-1", "fields": {"summary": "Fix login", "status": {"name": "Done"}, "priority": {"name": "High"}}},
+                {"key": "PROJ-22", "fields": {"summary": "Blocked task", "status": {"name": "Blocked"}, "assignee": null}}
+            ],
+            "count": 2
+        })
+    }
+
+    #[test]
+    fn test_render_issue_table_plain() {
+        let table = render_table(&issues(), false);
+        assert_eq!(
+            table,
+            "KEY      SUMMARY       STATUS   PRIORITY\n\
+             PROJ-1   Fix login     Done     High\n\
+             PROJ-22  Blocked task  Blocked\n"
+        );
+        assert!(!table.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_render_issue_table_colored() {
+        let table = render_table(&issues(), true);
+        assert!(table.contains(&format!("{}Done   {}", GREEN, RESET)));
+        assert!(table.contains(&format!("{}Blocked{}", RED, RESET)));
+        assert!(table.contains(&format!("{}High    {}", RED, RESET)));
+    }
+
+    #[test]
+    fn test_render_object_without_rows() {
+        let table = render_table(&json!({"key": "PROJ-1", "id": "10001"}), false);
+        assert_eq!(table, "KEY     ID\nPROJ-1  10001\n");
+    }
+
+    #[test]
+    fn test_truncate_long_cells() {
+        let long = "x".repeat(100);
+        assert_eq!(truncate(&long).chars().count(), MAX_CELL_WIDTH);
+        assert!(truncate(&long).ends_with('…'));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Where `--all` pagination sends each fetched page
+pub enum Sink {
+    /// Keep everything and return it as `items`
+    Collect,
+    /// JSONL on stdout as pages arrive (`--stream`)
+    Stdout,
+    /// Straight to a file as pages arrive (`--export`)
+    File(Export),
+}
+
+impl Sink {
+    /// Hand a page to the sink; returns the items only when collecting
+    pub fn write_page(&mut self, items: Vec<Value>) -> Result<Vec<Value>> {
+        match self {
+            Self::Collect => return Ok(items),
+            Self::Stdout => {
+                let mut out = io::stdout().lock();
+                for item in &items {
+                    writeln!(out, "{}", serde_json::to_string(item)?)?;
+                }
+                out.flush()?;
+            }
+            Self::File(export) => {
+                for item in &items {
+                    export.write(item)?;
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Result of the run: the collected items, or a summary of what was written elsewhere
+    pub fn finish(self, items: Vec<Value>, total: usize) -> Result<Value> {
+        Ok(match self {
+            Self::Collect => json!({"items": items, "total": total}),
+            Self::Stdout => json!({"streamed": true, "total": total}),
+            Self::File(export) => {
+                let path = export.finish()?;
+                json!({"exported": path, "total": total})
+            }
+        })
+    }
+}
+
+/// Incremental writer for `--export`: a JSON array, or NDJSON with `jsonl`
+pub struct Export {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    jsonl: bool,
+    written: usize,
+}
+
+impl Export {
+    pub fn create(path: &Path, jsonl: bool) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create export file {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        if !jsonl {
+            writer.write_all(b"[")?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer,
+            jsonl,
+            written: 0,
+        })
+    }
+
+    fn write(&mut self, item: &Value) -> Result<()> {
+        if self.jsonl {
+            serde_json::to_writer(&mut self.writer, item)?;
+            self.writer.write_all(b"\n")?;
+        } else {
+            self.writer
+                .write_all(if self.written == 0 { b"\n" } else { b",\n" })?;
+            serde_json::to_writer(&mut self.writer, item)?;
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<PathBuf> {
+        if !self.jsonl {
+            self.writer.write_all(b"\n]\n")?;
+        }
+        self.writer.flush()?;
+        Ok(self.path)
+    }
+}
+
+/// Truncate a freshly fetched page so the running total never exceeds `max`.
+/// Returns true once the cap has been reached and pagination should stop.
+pub fn apply_cap(page: &mut Vec<Value>, fetched: usize, max: Option<usize>) -> bool {
+    let Some(max) = max else {
+        return false;
+    };
+    let remaining = max.saturating_sub(fetched);
+    page.truncate(remaining);
+    fetched + page.len() >= max
+}
+
+/// Progress fragment for stderr, e.g. "fetched: 500/50000 (capped at 500)".
+pub fn progress(fetched: usize, total: u64, max: Option<usize>) -> String {
+    match max {
+        Some(max) => format!("fetched: {}/{} (capped at {})", fetched, total, max),
+        None => format!("fetched: {}/{}", fetched, total),
+    }
+}
+
+/// Report a fetched page on stderr: a `{"page", "fetched", "total"}` line with
+/// `--progress-json`, otherwise a prose line unless `--quiet`
+pub fn report_page(
+    config: &Config,
+    page: usize,
+    unit: &str,
+    count: usize,
+    fetched: usize,
+    total: u64,
+    max: Option<usize>,
+) {
+    if config.progress_json {
+        eprintln!("{}", progress_json(page, fetched, total));
+    } else if !config.quiet {
+        eprintln!(
+            "  Page {}: {} {} ({})",
+            page,
+            count,
+            unit,
+            progress(fetched, total, max)
+        );
+    }
+}
+
+fn progress_json(page: usize, fetched: usize, total: u64) -> Value {
+    json!({"page": page, "fetched": fetched, "total": total})
+}
+
+/// Clamp a requested page size to the most the API returns per request, noting it on stderr
+pub fn clamp_limit(limit: u32, max: u32, quiet: bool) -> u32 {
+    if limit <= max {
+        return limit;
+    }
+    if !quiet {
+        eprintln!(
+            "Note: --limit {} exceeds the per-request maximum of {}; using {} (use --all for more)",
+            limit, max, max
+        );
+    }
+    max
+}
+
+/// How far an `--all` run goes: `max` caps the total (`--max`), `page_size` sets the
+/// results per request (`--page-size`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Paging {
+    pub max: Option<usize>,
+    pub page_size: Option<u32>,
+}
+
+impl Paging {
+    /// `page_size` clamped to the per-request maximum (noted on stderr), or the maximum
+    /// itself when no page size was given
+    pub fn page_size(&self, max: u32, quiet: bool) -> u32 {
+        match self.page_size {
+            Some(size) if size > max => {
+                if !quiet {
+                    eprintln!(
+                        "Note: --page-size {} exceeds the per-request maximum of {}; using {}",
+                        size, max, max
+                    );
+                }
+                max
+            }
+            Some(size) => size.max(1),
+            None => max,
+        }
+    }
+}
+
+/// Warning for a single page that came back shorter than requested while more results
+/// remain, which happens when the server caps the page size below the API maximum.
+pub fn truncation_warning(
+    requested: u32,
+    count: usize,
+    total: Option<u64>,
+    has_more: bool,
+) -> Option<String> {
+    if !has_more || count >= requested as usize {
+        return None;
+    }
+    let total = total.map_or_else(|| "more".to_string(), |t| t.to_string());
+    Some(format!(
+        "Warning: the server returned {} of {} results (requested {}); use --all to fetch everything",
+        count, total, requested
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_cap_truncates_last_page() {
+        let mut page = vec![json!(1), json!(2), json!(3)];
+        assert!(apply_cap(&mut page, 8, Some(10)));
+        assert_eq!(page.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_cap_below_limit() {
+        let mut page = vec![json!(1), json!(2)];
+        assert!(!apply_cap(&mut page, 0, Some(10)));
+        assert_eq!(page.len(), 2);
+
+        assert!(!apply_cap(&mut page, 100, None));
+        assert_eq!(page.len(), 2);
+    }
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(100, 5000, None), "fetched: 100/5000");
+        assert_eq!(
+            progress(500, 50000, Some(500)),
+            "fetched: 500/50000 (capped at 500)"
+        );
+    }
+
+    #[test]
+    fn test_clamp_limit() {
+        assert_eq!(clamp_limit(50, 100, true), 50);
+        assert_eq!(clamp_limit(500, 100, true), 100);
+    }
+
+    #[test]
+    fn test_page_size() {
+        let mut paging = Paging::default();
+        assert_eq!(paging.page_size(100, true), 100);
+        paging.page_size = Some(25);
+        assert_eq!(paging.page_size(100, true), 25);
+        paging.page_size = Some(500);
+        assert_eq!(paging.page_size(100, true), 100);
+    }
+
+    #[test]
+    fn test_truncation_warning() {
+        assert_eq!(truncation_warning(100, 100, Some(500), true), None);
+        assert_eq!(truncation_warning(100, 40, Some(40), false), None);
+        assert_eq!(
+            truncation_warning(100, 50, Some(500), true).unwrap(),
+            "Warning: the server returned 50 of 500 results (requested 100); use --all to fetch everything"
+        );
+        assert!(
+            truncation_warning(100, 50, None, true)
+                .unwrap()
+                .contains("50 of more")
+        );
+    }
+
+    #[test]
+    fn test_progress_json() {
+        assert_eq!(
+            progress_json(3, 300, 5000).to_string(),
+            r#"{"page":3,"fetched":300,"total":5000}"#
+        );
+    }
+
+    fn export_to_string(jsonl: bool, items: &[Value]) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "atlassian-cli-test-export-{}-{}-{}.json",
+            std::process::id(),
+            jsonl,
+            items.len()
+        ));
+        let mut sink = Sink::File(Export::create(&path, jsonl).unwrap());
+        let kept = sink.write_page(items.to_vec()).unwrap();
+        assert!(kept.is_empty());
+        let result = sink.finish(kept, items.len()).unwrap();
+        assert_eq!(result["total"], items.len());
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        written
+    }
+
+    #[test]
+    fn test_export_json_array() {
+        let items = [json!({"key": "A-1"}), json!({"key": "A-2"})];
+        let written = export_to_string(false, &items);
+        assert_eq!(
+            serde_json::from_str::<Value>(&written).unwrap(),
+            json!(items)
+        );
+        assert_eq!(export_to_string(false, &[]).trim(), "[\n]");
+    }
+
+    #[test]
+    fn test_export_jsonl() {
+        let items = [json!({"key": "A-1"}), json!({"key": "A-2"})];
+        assert_eq!(
+            export_to_string(true, &items),
+            "{\"key\":\"A-1\"}\n{\"key\":\"A-2\"}\n"
+        );
+    }
+}
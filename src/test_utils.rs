@@ -5,6 +5,14 @@
 
 #[cfg(test)]
 use crate::config::{Config, PerformanceConfig};
+#[cfg(test)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(test)]
+use std::net::TcpListener;
+#[cfg(test)]
+use std::thread::JoinHandle;
+#[cfg(test)]
+use std::time::Duration;
 
 /// Creates a test configuration with sensible defaults
 #[cfg(test)]
@@ -16,7 +24,8 @@ pub fn create_test_config() -> Config {
         base_url: "https://test.atlassian.net".to_string(),
         performance: PerformanceConfig {
             request_timeout_ms: 30000,
-            rate_limit_delay_ms: 200,
+            requests_per_second: 5.0,
+            ..Default::default()
         },
         ..Default::default()
     }
@@ -51,6 +60,117 @@ pub fn create_test_config_with_filters(projects: Vec<String>, spaces: Vec<String
     config
 }
 
+/// A request received by a [`serve`] stub
+#[cfg(test)]
+pub struct Request {
+    /// Request line, e.g. `GET http://127.0.0.1:1234/rest/api/3/myself HTTP/1.1`
+    pub line: String,
+    /// Header lines, lowercased (`name: value`)
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+/// Everything a [`serve`] stub saw before it ran out of responses
+#[cfg(test)]
+pub struct Served {
+    pub requests: Vec<Request>,
+    /// Connections accepted; 1 when every request reused the first one
+    pub connections: usize,
+}
+
+/// Answer requests on a local HTTP stub with canned `(status, JSON body)` responses, in
+/// order, then stop. Returns the stub's base URL (see [`use_stub`]) and a handle that
+/// yields the requests once every response has been sent.
+#[cfg(test)]
+pub fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, JoinHandle<Served>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let mut served = Served {
+            requests: Vec::new(),
+            connections: 0,
+        };
+        let mut responses = responses.into_iter().peekable();
+        while responses.peek().is_some() {
+            let (stream, _) = listener.accept().unwrap();
+            served.connections += 1;
+            // An idle keep-alive connection the client abandoned must not block the stub
+            stream
+                .set_read_timeout(Some(Duration::from_secs(2)))
+                .unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut stream = stream;
+            while responses.peek().is_some() {
+                let Some(request) = read_request(&mut reader) else {
+                    break;
+                };
+                let (status, body) = responses.next().unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                served.requests.push(request);
+            }
+        }
+        served
+    });
+
+    (base_url, handle)
+}
+
+/// [`serve`] a single response and yield the one request it answered
+#[cfg(test)]
+pub fn serve_once(status: &'static str, body: &'static str) -> (String, JoinHandle<Request>) {
+    let (base_url, served) = serve(vec![(status, body)]);
+    let handle = std::thread::spawn(move || served.join().unwrap().requests.remove(0));
+    (base_url, handle)
+}
+
+/// Send a config's requests to a [`serve`] stub. The explicit proxy pointing at the stub
+/// keeps HTTP(S)_PROXY from the environment out of the way.
+#[cfg(test)]
+pub fn use_stub(config: &mut Config, base_url: &str) {
+    config.base_url = base_url.to_string();
+    config.performance.proxy = Some(base_url.to_string());
+}
+
+/// One request from `reader`, or None once the client closes the connection
+#[cfg(test)]
+fn read_request(reader: &mut impl BufRead) -> Option<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+        return None;
+    }
+
+    let mut headers = Vec::new();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        let header = header.trim().to_lowercase();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(len) = header.strip_prefix("content-length:") {
+            content_length = len.trim().parse().unwrap();
+        }
+        headers.push(header);
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    Some(Request {
+        line: line.trim().to_string(),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,148 @@
+use crate::config::Config;
+use crate::error::AppError;
+use regex::Regex;
+use std::sync::LazyLock;
+
+const REDACTED: &str = "***";
+
+static AUTH_HEADER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(authorization"?\s*[:=]\s*"?(?:basic|bearer)\s+)[A-Za-z0-9+/=._~-]+"#)
+        .unwrap()
+});
+
+/// Replace every occurrence of `secrets` and any `Authorization: Basic|Bearer <value>`
+/// header value in `text` with `***`.
+pub fn redact(text: &str, secrets: &[&str]) -> String {
+    let mut result = AUTH_HEADER_RE
+        .replace_all(text, format!("${{1}}{}", REDACTED))
+        .into_owned();
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        result = result.replace(secret, REDACTED);
+    }
+    result
+}
+
+/// Redact the configured API token (raw and as an encoded Basic credential) from `text`.
+pub fn redact_credentials(text: &str, config: &Config) -> String {
+    let Some(token) = config.token.as_deref() else {
+        return redact(text, &[]);
+    };
+    let header = crate::http::auth_header(config);
+    let encoded = header.split_once(' ').map_or("", |(_, value)| value);
+    redact(text, &[token, encoded])
+}
+
+/// Rebuild an error with credentials scrubbed from its full message chain,
+/// keeping its [`AppError`] classification.
+pub fn redact_error(error: anyhow::Error, config: &Config) -> anyhow::Error {
+    let app = AppError::classify(&error);
+    let message = redact_credentials(app.message(), config);
+    anyhow::Error::new(app.with_message(message))
+}
+
+/// Check a JQL/CQL query for unterminated quotes and unbalanced parentheses.
+/// This is not a parser; it only catches mistakes that would otherwise come back as an opaque 400.
+pub fn check_query_syntax(query: &str, language: &str) -> anyhow::Result<()> {
+    let mut depth: usize = 0;
+    let mut quote: Option<(char, usize)> = None;
+    let mut escaped = false;
+
+    for (pos, c) in query.chars().enumerate() {
+        if let Some((q, _)) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => quote = Some((c, pos)),
+            '(' => depth += 1,
+            ')' => {
+                if depth == 0 {
+                    anyhow::bail!(
+                        "Invalid {}: unexpected ')' at position {}",
+                        language,
+                        pos + 1
+                    );
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((q, pos)) = quote {
+        anyhow::bail!(
+            "Invalid {}: unterminated {} quote opened at position {}",
+            language,
+            q,
+            pos + 1
+        );
+    }
+    if depth > 0 {
+        anyhow::bail!("Invalid {}: {} unclosed '('", language, depth);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_config;
+
+    #[test]
+    fn test_redact_secret() {
+        assert_eq!(
+            redact("token abc123 leaked", &["abc123"]),
+            "token *** leaked"
+        );
+    }
+
+    #[test]
+    fn test_redact_ignores_empty_secret() {
+        assert_eq!(redact("unchanged", &[""]), "unchanged");
+    }
+
+    #[test]
+    fn test_redact_authorization_header() {
+        assert_eq!(
+            redact("Authorization: Basic dXNlcjpwYXNz", &[]),
+            "Authorization: Basic ***"
+        );
+        assert_eq!(
+            redact(r#"{"authorization": "Bearer abc.def"}"#, &[]),
+            r#"{"authorization": "Bearer ***"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_error_never_contains_token() {
+        let config = create_test_config();
+        let token = config.token();
+        let encoded = crate::http::auth_header(&config)
+            .split_once(' ')
+            .unwrap()
+            .1
+            .to_string();
+        let error = anyhow::anyhow!("Failed to get issue (401): bad token {}", token)
+            .context(format!("request with credentials {}", encoded));
+
+        let message = format!("{:?}", redact_error(error, &config));
+        assert!(!message.contains(token));
+        assert!(!message.contains(&encoded));
+        assert!(message.contains("Failed to get issue (401)"));
+    }
+
+    #[test]
+    fn test_check_query_syntax_names_language() {
+        assert!(check_query_syntax("summary ~ \"a (b\"", "JQL").is_ok());
+        let err = check_query_syntax("(status = Done", "JQL").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid JQL: 1 unclosed '('");
+    }
+}